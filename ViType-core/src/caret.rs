@@ -0,0 +1,379 @@
+use crate::edit_script::{edit_script_at_caret, EditOp};
+use crate::{is_word_boundary, HistorySegment, VitypeEngine, WordSegment};
+
+// ==================== Caret Methods on VitypeEngine ====================
+//
+// The caret is a character index into `buffer`; `None` means the end of the word.
+// Segments to the right of the current word wait in `following`, mirroring
+// `history` on the left. Positions inside a word map to `raw_buffer` through
+// provenance, and edits replay the word's keystrokes, so tones and shapes keep
+// applying to the edited word.
+
+impl VitypeEngine {
+    pub(crate) fn set_history_word_limit(&mut self, limit: usize) {
+        self.history_word_limit = limit.max(1);
+        self.trim_history_to_word_limit();
+        self.trim_following_to_word_limit();
+    }
+
+    fn caret_index(&self) -> usize {
+        self.caret
+            .unwrap_or(self.buffer.len())
+            .min(self.buffer.len())
+    }
+
+    fn set_caret(&mut self, caret: usize) {
+        self.caret = (caret < self.buffer.len()).then_some(caret);
+    }
+
+    /// Whether the caret sits inside the current word rather than at its end.
+    pub(super) fn is_caret_inside_word(&self) -> bool {
+        self.caret.is_some_and(|caret| caret < self.buffer.len())
+    }
+
+    /// The number of raw keystrokes that produce the output before `caret`: the
+    /// earliest keystroke of any char from the caret on.
+    fn raw_index_for_caret(&self, caret: usize) -> usize {
+        self.provenance
+            .get(caret..)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|keys| keys.first().copied())
+            .min()
+            .unwrap_or(self.raw_buffer.len())
+    }
+
+    /// The number of leading chars produced by the first `raw_caret` keystrokes.
+    fn caret_for_raw_index(&self, raw_caret: usize) -> usize {
+        self.provenance
+            .iter()
+            .take_while(|keys| keys.first().is_some_and(|key| *key < raw_caret))
+            .count()
+    }
+
+    /// Replays `raw` as the current word and puts the caret after the output of
+    /// its first `raw_caret` keystrokes.
+    fn rebuild_word_at_caret(&mut self, raw: &[char], raw_caret: usize) {
+        if raw.is_empty() {
+            self.reset_current_word();
+            return;
+        }
+        let rebuilt = self.rebuild_current_word_from_raw(raw);
+        self.adopt_current_word_state_from(rebuilt);
+        let caret = if raw_caret >= raw.len() {
+            self.buffer.len()
+        } else {
+            self.caret_for_raw_index(raw_caret)
+        };
+        self.set_caret(caret);
+    }
+
+    fn load_word(&mut self, word: WordSegment, caret: usize) {
+        self.buffer = word.buffer;
+        self.raw_buffer = word.raw_buffer;
+        self.provenance = word.provenance;
+        self.word_snapshots.clear();
+        self.is_foreign_mode = word.is_foreign_mode;
+        self.transforms_locked = word.transforms_locked;
+        self.clear_transform_state();
+        self.set_caret(caret);
+    }
+
+    /// Moves the current word, whose start is at the caret, into `following`.
+    fn park_current_word(&mut self) {
+        if !self.buffer.is_empty() {
+            self.following.push_front(HistorySegment::Word(WordSegment {
+                buffer: std::mem::take(&mut self.buffer),
+                raw_buffer: std::mem::take(&mut self.raw_buffer),
+                provenance: std::mem::take(&mut self.provenance),
+                is_foreign_mode: self.is_foreign_mode,
+                transforms_locked: self.transforms_locked,
+            }));
+        }
+        self.reset_current_word();
+        self.trim_following_to_word_limit();
+    }
+
+    fn push_following_boundary(&mut self, ch: char) {
+        match self.following.front_mut() {
+            Some(HistorySegment::Boundary(chars)) => chars.insert(0, ch),
+            _ => self
+                .following
+                .push_front(HistorySegment::Boundary(vec![ch])),
+        }
+    }
+
+    fn trim_following_to_word_limit(&mut self) {
+        let mut word_count = self
+            .following
+            .iter()
+            .filter(|segment| matches!(segment, HistorySegment::Word(_)))
+            .count();
+        while word_count > self.history_word_limit {
+            if let Some(HistorySegment::Word(_)) = self.following.pop_back() {
+                word_count -= 1;
+            }
+        }
+    }
+
+    /// With the caret right before a parked word, makes that word current.
+    fn enter_following_word(&mut self) {
+        if !self.buffer.is_empty() {
+            return;
+        }
+        if let Some(HistorySegment::Word(_)) = self.following.front() {
+            if let Some(HistorySegment::Word(word)) = self.following.pop_front() {
+                self.load_word(word, 0);
+            }
+        }
+    }
+
+    fn step_left(&mut self) -> bool {
+        let caret = self.caret_index();
+        if caret > 0 {
+            self.clear_transform_state();
+            self.set_caret(caret - 1);
+            return true;
+        }
+        match self.history.back_mut() {
+            None => false,
+            Some(HistorySegment::Boundary(chars)) => {
+                let Some(ch) = chars.pop() else {
+                    return false;
+                };
+                if chars.is_empty() {
+                    self.history.pop_back();
+                }
+                self.park_current_word();
+                self.push_following_boundary(ch);
+                if matches!(self.history.back(), Some(HistorySegment::Word(_))) {
+                    self.restore_last_word_from_history();
+                }
+                true
+            }
+            Some(HistorySegment::Word(_)) => {
+                self.park_current_word();
+                self.restore_last_word_from_history();
+                self.step_left()
+            }
+        }
+    }
+
+    fn step_right(&mut self) -> bool {
+        let caret = self.caret_index();
+        if caret < self.buffer.len() {
+            self.clear_transform_state();
+            self.set_caret(caret + 1);
+            return true;
+        }
+        match self.following.pop_front() {
+            None => false,
+            Some(HistorySegment::Boundary(mut chars)) => {
+                let ch = chars.remove(0);
+                if !chars.is_empty() {
+                    self.following.push_front(HistorySegment::Boundary(chars));
+                }
+                self.commit_current_word_to_history_if_needed();
+                self.push_boundary_to_history(ch);
+                self.reset_current_word();
+                self.enter_following_word();
+                true
+            }
+            Some(HistorySegment::Word(word)) => {
+                self.commit_current_word_to_history_if_needed();
+                self.trim_history_to_word_limit();
+                self.load_word(word, 0);
+                self.step_right()
+            }
+        }
+    }
+
+    /// Mirrors a caret move by `delta` characters (negative is left). Returns how
+    /// far the caret actually moved; it stops at the ends of the known text.
+    pub(crate) fn move_cursor(&mut self, delta: isize) -> isize {
+        self.dismiss_han_nom_candidates();
+        self.clear_predictions();
+        let mut moved = 0;
+        while moved > delta && self.step_left() {
+            moved -= 1;
+        }
+        while moved < delta && self.step_right() {
+            moved += 1;
+        }
+        self.refresh_literal_context();
+        moved
+    }
+
+    /// Types `input` at the caret. At the end of a word this is `process` as an
+    /// edit script; inside a word the key is inserted into the raw keystrokes and
+    /// the word is replayed. A boundary inside a word splits it.
+    pub(crate) fn process_at_caret(&mut self, input: &str) -> Option<Vec<EditOp>> {
+        let mut chars = input.chars();
+        let (Some(ch), None) = (chars.next(), chars.next()) else {
+            return None;
+        };
+        self.enter_following_word();
+        if !self.is_caret_inside_word() {
+            self.caret = None;
+            return self.process_edit_script(input, false);
+        }
+        self.dismiss_han_nom_candidates();
+        self.clear_predictions();
+
+        let old = self.buffer.clone();
+        let caret = self.caret_index();
+        let raw_caret = self.raw_index_for_caret(caret);
+        let mut raw = self.raw_buffer.clone();
+
+        if is_word_boundary(ch, self.input_method) {
+            let right_raw = raw.split_off(raw_caret);
+            let right = self.rebuild_current_word_from_raw(&right_raw);
+            self.rebuild_word_at_caret(&raw, raw.len());
+            let mut new = self.buffer.clone();
+            new.push(ch);
+            let new_caret = new.len();
+            new.extend(&right.buffer);
+
+            self.commit_current_word_to_history_if_needed();
+            self.push_boundary_to_history(ch);
+            self.reset_current_word();
+            self.adopt_current_word_state_from(right);
+            self.set_caret(0);
+            return Some(edit_script_at_caret(&old, caret, &new, new_caret));
+        }
+
+        raw.insert(raw_caret, ch);
+        self.rebuild_word_at_caret(&raw, raw_caret + 1);
+        Some(edit_script_at_caret(
+            &old,
+            caret,
+            &self.buffer,
+            self.caret_index(),
+        ))
+    }
+
+    /// Removes the keystrokes behind the output characters in `start..end` and
+    /// replays the word; the caret lands where the removed text was.
+    fn delete_output_range(&mut self, start: usize, end: usize) -> Vec<EditOp> {
+        let old = self.buffer.clone();
+        let old_caret = self.caret_index();
+        let raw_start = self.raw_index_for_caret(start);
+        let raw_end = self
+            .raw_index_for_caret(end)
+            .max(raw_start + 1)
+            .min(self.raw_buffer.len());
+        let mut raw = self.raw_buffer.clone();
+        raw.drain(raw_start..raw_end);
+        self.rebuild_word_at_caret(&raw, raw_start);
+        edit_script_at_caret(&old, old_caret, &self.buffer, self.caret_index())
+    }
+
+    /// Deletes the character after the caret. Deleting the only boundary between
+    /// two words joins them into one word.
+    pub(crate) fn delete_forward(&mut self) -> Option<Vec<EditOp>> {
+        self.dismiss_han_nom_candidates();
+        self.clear_predictions();
+        self.enter_following_word();
+        let caret = self.caret_index();
+        if caret < self.buffer.len() {
+            let ops = self.delete_output_range(caret, caret + 1);
+            self.refresh_literal_context();
+            return Some(ops);
+        }
+
+        let Some(HistorySegment::Boundary(chars)) = self.following.front_mut() else {
+            return None;
+        };
+        let ch = chars.remove(0);
+        if chars.is_empty() {
+            self.following.pop_front();
+        }
+        let mut old = self.buffer.clone();
+        let old_caret = old.len();
+        old.push(ch);
+        if !self.buffer.is_empty() {
+            if let Some(HistorySegment::Word(_)) = self.following.front() {
+                if let Some(HistorySegment::Word(next)) = self.following.pop_front() {
+                    old.extend(&next.buffer);
+                    let raw_caret = self.raw_buffer.len();
+                    let mut raw = self.raw_buffer.clone();
+                    raw.extend(&next.raw_buffer);
+                    self.rebuild_word_at_caret(&raw, raw_caret);
+                }
+            }
+        }
+        self.refresh_literal_context();
+        Some(edit_script_at_caret(
+            &old,
+            old_caret,
+            &self.buffer,
+            self.caret_index(),
+        ))
+    }
+
+    /// Backspace at the caret. Deleting the only boundary between two words joins
+    /// them into one word.
+    pub(crate) fn delete_backward(&mut self) -> Option<Vec<EditOp>> {
+        self.dismiss_han_nom_candidates();
+        self.clear_predictions();
+        self.enter_following_word();
+        let caret = self.caret_index();
+        if caret > 0 && caret == self.buffer.len() {
+            let old = self.buffer.clone();
+            self.delete_last_character_in_current_word();
+            self.caret = None;
+            self.refresh_literal_context();
+            return Some(edit_script_at_caret(
+                &old,
+                old.len(),
+                &self.buffer,
+                self.buffer.len(),
+            ));
+        }
+        if caret > 0 {
+            let ops = self.delete_output_range(caret - 1, caret);
+            self.refresh_literal_context();
+            return Some(ops);
+        }
+
+        let ops = match self.history.back_mut() {
+            None => return None,
+            Some(HistorySegment::Word(_)) => {
+                self.park_current_word();
+                self.restore_last_word_from_history();
+                return self.delete_backward();
+            }
+            Some(HistorySegment::Boundary(chars)) => {
+                let ch = chars.pop()?;
+                if chars.is_empty() {
+                    self.history.pop_back();
+                }
+                let joins_previous_word = !self.buffer.is_empty()
+                    && matches!(self.history.back(), Some(HistorySegment::Word(_)));
+                match self.history.pop_back() {
+                    Some(HistorySegment::Word(previous)) if joins_previous_word => {
+                        let mut old = previous.buffer.clone();
+                        old.push(ch);
+                        let old_caret = old.len();
+                        old.extend(&self.buffer);
+                        let raw_caret = previous.raw_buffer.len();
+                        let mut raw = previous.raw_buffer;
+                        raw.extend(&self.raw_buffer);
+                        self.rebuild_word_at_caret(&raw, raw_caret);
+                        edit_script_at_caret(&old, old_caret, &self.buffer, self.caret_index())
+                    }
+                    other => {
+                        if let Some(segment) = other {
+                            self.history.push_back(segment);
+                        }
+                        let mut old = vec![ch];
+                        old.extend(&self.buffer);
+                        edit_script_at_caret(&old, 1, &self.buffer, 0)
+                    }
+                }
+            }
+        };
+        self.refresh_literal_context();
+        Some(ops)
+    }
+}
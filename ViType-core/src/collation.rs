@@ -0,0 +1,157 @@
+use once_cell::sync::Lazy;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::common::lower_char;
+use crate::diacritics::{apply_shape, split_vowel_and_tone, VowelShape};
+
+/// Onset digraphs and trigraphs that dictionaries file as single letters.
+const DIGRAPHS: [&str; 11] = [
+    "ch", "gh", "gi", "kh", "ng", "ngh", "nh", "ph", "qu", "th", "tr",
+];
+
+/// Secondary weights in dictionary order: unmarked, huyền, hỏi, ngã, sắc, nặng.
+const TONE_ORDER: [Option<char>; 6] = [None, Some('f'), Some('r'), Some('x'), Some('s'), Some('j')];
+
+const WHITESPACE_WEIGHT: u32 = 1;
+const SYMBOL_BASE: u32 = 0x100;
+const LETTER_BASE: u32 = 0x1000_0000;
+const OTHER_BASE: u32 = 0x2000_0000;
+
+// ==================== Options ====================
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub(crate) enum CaseOrder {
+    #[default]
+    Ignore = 0,
+    LowerFirst = 1,
+    UpperFirst = 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub(crate) struct CollationOptions {
+    pub(crate) case_order: CaseOrder,
+    /// File "ch", "ngh", "tr", … after every word starting with their first letter.
+    /// Units are matched at each syllable start, which in written Vietnamese is
+    /// after a space or punctuation; unseparated compounds (identifiers, loans)
+    /// get a unit only at their start.
+    pub(crate) digraphs_as_units: bool,
+}
+
+// ==================== Alphabet ====================
+
+/// Primary weights for lowercase letters and onset digraphs: a ă â b c ch d đ e ê …
+/// Shaped vowels come from the shape tables so they follow their base letter.
+static PRIMARY_WEIGHTS: Lazy<HashMap<String, u32>> = Lazy::new(|| {
+    let mut units: Vec<String> = Vec::new();
+    for base in 'a'..='z' {
+        units.push(base.to_string());
+        for shape in [VowelShape::Breve, VowelShape::Circumflex, VowelShape::Horn] {
+            if let Some(shaped) = apply_shape(base, shape) {
+                if shaped != base && !units.contains(&shaped.to_string()) {
+                    units.push(shaped.to_string());
+                }
+            }
+        }
+        if base == 'd' {
+            units.push("đ".to_string());
+        }
+        let mut following: Vec<&str> = DIGRAPHS
+            .iter()
+            .copied()
+            .filter(|digraph| digraph.starts_with(base))
+            .collect();
+        following.sort();
+        units.extend(following.into_iter().map(str::to_string));
+    }
+    units
+        .into_iter()
+        .enumerate()
+        .map(|(rank, unit)| (unit, LETTER_BASE + rank as u32))
+        .collect()
+});
+
+// ==================== Helper Functions ====================
+
+fn primary_weight(ch: char) -> u32 {
+    if ch.is_whitespace() {
+        return WHITESPACE_WEIGHT;
+    }
+    if let Some(weight) = PRIMARY_WEIGHTS.get(&ch.to_string()) {
+        return *weight;
+    }
+    if ch.is_alphabetic() {
+        return OTHER_BASE + ch as u32;
+    }
+    SYMBOL_BASE + ch as u32
+}
+
+/// Matches the longest onset digraph at the start of `chars`, ignoring tone marks.
+fn digraph_at(chars: &[char]) -> Option<(usize, u32)> {
+    [3, 2].iter().find_map(|length| {
+        let unit: String = chars.get(..*length)?.iter().collect();
+        if !DIGRAPHS.contains(&unit.as_str()) {
+            return None;
+        }
+        Some((*length, *PRIMARY_WEIGHTS.get(&unit)?))
+    })
+}
+
+// ==================== Public Functions ====================
+
+/// Byte string whose lexicographic order is Vietnamese dictionary order: letters
+/// first, then tones, then (optionally) case. Suitable for memcmp-based sorting.
+/// Text is NFC-normalised first, so decomposed input sorts like precomposed.
+pub(crate) fn sort_key(text: &str, options: CollationOptions) -> Vec<u8> {
+    let chars: Vec<char> = text.nfc().collect();
+    let mut bases = Vec::with_capacity(chars.len());
+    let mut tones = Vec::with_capacity(chars.len());
+    let mut cases = Vec::with_capacity(chars.len());
+    for ch in &chars {
+        let (base, tone) = split_vowel_and_tone(*ch);
+        bases.push(lower_char(base));
+        tones.push(
+            TONE_ORDER
+                .iter()
+                .position(|order| *order == tone)
+                .unwrap_or(0) as u32
+                + 1,
+        );
+        cases.push(match (options.case_order, ch.is_uppercase()) {
+            (CaseOrder::LowerFirst, true) | (CaseOrder::UpperFirst, false) => 2,
+            _ => 1,
+        });
+    }
+
+    let mut weights: Vec<u32> = Vec::with_capacity(chars.len() * 3 + 2);
+    let mut index = 0;
+    while index < bases.len() {
+        let at_syllable_start = index == 0 || !bases[index - 1].is_alphabetic();
+        if options.digraphs_as_units && at_syllable_start {
+            if let Some((length, weight)) = digraph_at(&bases[index..]) {
+                weights.push(weight);
+                index += length;
+                continue;
+            }
+        }
+        weights.push(primary_weight(bases[index]));
+        index += 1;
+    }
+
+    weights.push(0);
+    weights.extend(tones);
+    if options.case_order != CaseOrder::Ignore {
+        weights.push(0);
+        weights.extend(cases);
+    }
+    weights
+        .iter()
+        .flat_map(|weight| weight.to_be_bytes())
+        .collect()
+}
+
+pub(crate) fn compare(a: &str, b: &str, options: CollationOptions) -> Ordering {
+    sort_key(a, options).cmp(&sort_key(b, options))
+}
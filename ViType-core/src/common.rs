@@ -12,7 +12,7 @@ pub(crate) enum OutputEncoding {
 }
 
 #[repr(C)]
-#[derive(Clone, Copy, PartialEq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
 pub(crate) enum InputMethod {
     #[default]
     Telex = 0,
@@ -20,13 +20,23 @@ pub(crate) enum InputMethod {
 }
 
 #[repr(C)]
-#[derive(Clone, Copy, PartialEq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 pub(crate) enum TonePlacement {
     #[default]
     Orthographic = 0,
     NucleusOnly = 1,
 }
 
+/// Spelling of a final i/y after h, k, l, m, s, t ("kĩ"/"kỹ", "lí"/"lý").
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub(crate) enum IYStyle {
+    #[default]
+    Unchanged = 0,
+    PreferI = 1,
+    PreferY = 2,
+}
+
 #[derive(Clone, Copy, PartialEq)]
 pub(crate) enum WTransformKind {
     None,
@@ -57,6 +67,10 @@ pub(crate) fn lower_char(ch: char) -> char {
     ch.to_lowercase().next().unwrap_or(ch)
 }
 
+pub(crate) fn lower_string(chars: &[char]) -> String {
+    chars.iter().map(|ch| lower_char(*ch)).collect()
+}
+
 pub(crate) fn is_vowel(ch: char) -> bool {
     if ch.is_ascii() {
         let byte = ch as u8;
@@ -0,0 +1,212 @@
+use crate::common::{lower_char, TonePlacement};
+use crate::lexicon::{Lexicon, DEFAULT_LEXICON};
+use crate::syllable::{compose_syllable, is_vietnamese_letter, match_case, word_tokens};
+use crate::{HistorySegment, VitypeEngine};
+
+/// A variant must be this much more likely (natural log, ≈ 8×) before it is reported.
+const MIN_LOG_MARGIN: f64 = 2.0;
+
+// ==================== Confusion ====================
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ConfusionKind {
+    HoiNga,
+    ChTr,
+    SX,
+    DGiR,
+    FinalNNg,
+    FinalTC,
+}
+
+/// A valid syllable that is probably a misspelling of `suggestion` in its context.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Confusion {
+    pub(crate) byte_start: usize,
+    pub(crate) byte_end: usize,
+    pub(crate) char_start: usize,
+    pub(crate) char_end: usize,
+    pub(crate) kind: ConfusionKind,
+    pub(crate) suggestion: String,
+    pub(crate) confidence: f64,
+}
+
+// ==================== Helper Functions ====================
+
+fn onset_alternatives(onset: &str) -> &'static [(&'static str, ConfusionKind)] {
+    match onset {
+        "ch" => &[("tr", ConfusionKind::ChTr)],
+        "tr" => &[("ch", ConfusionKind::ChTr)],
+        "s" => &[("x", ConfusionKind::SX)],
+        "x" => &[("s", ConfusionKind::SX)],
+        "d" => &[("gi", ConfusionKind::DGiR), ("r", ConfusionKind::DGiR)],
+        "gi" => &[("d", ConfusionKind::DGiR), ("r", ConfusionKind::DGiR)],
+        "r" => &[("d", ConfusionKind::DGiR), ("gi", ConfusionKind::DGiR)],
+        _ => &[],
+    }
+}
+
+fn coda_alternatives(coda: &str) -> &'static [(&'static str, ConfusionKind)] {
+    match coda {
+        "n" => &[("ng", ConfusionKind::FinalNNg)],
+        "ng" => &[("n", ConfusionKind::FinalNNg)],
+        "t" => &[("c", ConfusionKind::FinalTC)],
+        "c" => &[("t", ConfusionKind::FinalTC)],
+        _ => &[],
+    }
+}
+
+/// Valid syllables one confusable edit away from the lowercase `word`.
+fn confusable_variants(
+    word: &[char],
+    tone_placement: TonePlacement,
+) -> Vec<(String, ConfusionKind)> {
+    let Ok(Some(parts)) = VitypeEngine::for_word(word, tone_placement).validate_syllable() else {
+        return Vec::new();
+    };
+    let onset = parts.onset_lower();
+    let coda = parts.coda_lower();
+    let nucleus = &parts.nucleus;
+    let starts_with_i = nucleus.first() == Some(&'i');
+    let mut variants = Vec::new();
+
+    let other_tone = match parts.tone {
+        Some('r') => Some('x'),
+        Some('x') => Some('r'),
+        _ => None,
+    };
+    if let Some(tone) = other_tone {
+        if let Some(variant) = compose_syllable(&onset, nucleus, &coda, Some(tone), tone_placement)
+        {
+            variants.push((variant, ConfusionKind::HoiNga));
+        }
+    }
+
+    // "gì" is written with a bare g before i; treat it as the gi onset.
+    let effective_onset = if onset == "g" && starts_with_i {
+        "gi"
+    } else {
+        onset.as_str()
+    };
+    for (replacement, kind) in onset_alternatives(effective_onset) {
+        let replacement = if *replacement == "gi" && starts_with_i {
+            "g"
+        } else {
+            replacement
+        };
+        if let Some(variant) =
+            compose_syllable(replacement, nucleus, &coda, parts.tone, tone_placement)
+        {
+            variants.push((variant, *kind));
+        }
+    }
+
+    for (replacement, kind) in coda_alternatives(&coda) {
+        if let Some(variant) =
+            compose_syllable(&onset, nucleus, replacement, parts.tone, tone_placement)
+        {
+            variants.push((variant, *kind));
+        }
+    }
+
+    variants
+}
+
+fn only_whitespace_between(text: &str, start: usize, end: usize) -> bool {
+    text[start..end].chars().all(char::is_whitespace)
+}
+
+/// Flags known syllables whose confusable variant (hỏi/ngã, ch/tr, s/x, d/gi/r,
+/// final n/ng and t/c) is far more likely given the neighbouring syllables.
+pub(crate) fn find_confusions(
+    text: &str,
+    lexicon: &Lexicon,
+    tone_placement: TonePlacement,
+) -> Vec<Confusion> {
+    let tokens = word_tokens(text);
+    let words: Vec<String> = tokens
+        .iter()
+        .map(|token| token.chars.iter().map(|ch| lower_char(*ch)).collect())
+        .collect();
+
+    let mut confusions = Vec::new();
+    for (index, token) in tokens.iter().enumerate() {
+        if !token.chars.iter().all(|ch| is_vietnamese_letter(*ch)) {
+            continue;
+        }
+
+        let previous = index
+            .checked_sub(1)
+            .filter(|prev| only_whitespace_between(text, tokens[*prev].byte_end, token.byte_start))
+            .map(|prev| words[prev].as_str());
+        let next = tokens
+            .get(index + 1)
+            .filter(|next| only_whitespace_between(text, token.byte_end, next.byte_start))
+            .map(|_| words[index + 1].as_str());
+
+        let word = &words[index];
+        // A word the lexicon has never seen has no context to compare against.
+        if !lexicon.contains(word) {
+            continue;
+        }
+        let lower: Vec<char> = word.chars().collect();
+        let original_score = lexicon.context_score(previous, word, next);
+
+        let mut best: Option<(f64, String, ConfusionKind)> = None;
+        for (variant, kind) in confusable_variants(&lower, tone_placement) {
+            if !lexicon.contains(&variant) {
+                continue;
+            }
+            let score = lexicon.context_score(previous, &variant, next);
+            if best
+                .as_ref()
+                .is_none_or(|(best_score, _, _)| score > *best_score)
+            {
+                best = Some((score, variant, kind));
+            }
+        }
+
+        let Some((score, variant, kind)) = best else {
+            continue;
+        };
+        let margin = score - original_score;
+        if margin < MIN_LOG_MARGIN {
+            continue;
+        }
+
+        confusions.push(Confusion {
+            byte_start: token.byte_start,
+            byte_end: token.byte_end,
+            char_start: token.char_start,
+            char_end: token.char_end,
+            kind,
+            suggestion: match_case(&token.chars, &variant),
+            confidence: 1.0 / (1.0 + (-margin).exp()),
+        });
+    }
+    confusions
+}
+
+// ==================== Confusable Methods on VitypeEngine ====================
+
+impl VitypeEngine {
+    pub(crate) fn check_confusables(&self, text: &str) -> Vec<Confusion> {
+        find_confusions(text, &DEFAULT_LEXICON, self.tone_placement)
+    }
+
+    /// The committed words and separators still held in history, oldest first.
+    pub(crate) fn history_text(&self) -> String {
+        let mut text = String::new();
+        for segment in &self.history {
+            match segment {
+                HistorySegment::Word(word) => text.extend(word.buffer.iter()),
+                HistorySegment::Boundary(chars) => text.extend(chars.iter()),
+            }
+        }
+        text
+    }
+
+    /// Checks the committed words in history; spans are relative to `history_text()`.
+    pub(crate) fn check_history_confusables(&self) -> Vec<Confusion> {
+        self.check_confusables(&self.history_text())
+    }
+}
@@ -1,5 +1,16 @@
+use unicode_normalization::UnicodeNormalization;
+
 use crate::common::{lower_char, TONED_TO_BASE, VOWEL_TO_TONED};
 
+/// Internal Telex tone keys and the combining marks they stand for.
+const TONE_MARKS: [(char, char); 5] = [
+    ('s', '\u{301}'),
+    ('f', '\u{300}'),
+    ('r', '\u{309}'),
+    ('x', '\u{303}'),
+    ('j', '\u{323}'),
+];
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub(crate) enum VowelShape {
     Circumflex,
@@ -21,6 +32,40 @@ pub(crate) fn apply_tone(base: char, tone: Option<char>) -> Option<char> {
     }
 }
 
+/// `apply_tone` for letters outside the Vietnamese table: the tone mark is
+/// composed with Unicode, which only works where a precomposed letter exists
+/// ("ü" takes "ǘ", "ŏ" takes nothing).
+pub(crate) fn compose_tone(letter: char, tone: Option<char>) -> Option<char> {
+    if let Some(toned) = apply_tone(letter, tone) {
+        return Some(toned);
+    }
+    let (_, mark) = TONE_MARKS.iter().find(|(key, _)| Some(*key) == tone)?;
+    let mut composed = [letter, *mark].into_iter().nfc();
+    let toned = composed.next()?;
+    composed.next().is_none().then_some(toned)
+}
+
+/// `split_vowel_and_tone` for letters outside the Vietnamese table, undoing
+/// `compose_tone`.
+pub(crate) fn decompose_tone(ch: char) -> (char, Option<char>) {
+    let split = split_vowel_and_tone(ch);
+    if split.1.is_some() {
+        return split;
+    }
+    let decomposed: Vec<char> = ch.nfd().collect();
+    let [rest @ .., mark] = decomposed.as_slice() else {
+        return split;
+    };
+    let Some((tone_key, _)) = TONE_MARKS.iter().find(|(_, tone_mark)| tone_mark == mark) else {
+        return split;
+    };
+    let base: Vec<char> = rest.iter().copied().nfc().collect();
+    match base.as_slice() {
+        [base] => (*base, Some(*tone_key)),
+        _ => split,
+    }
+}
+
 pub(crate) fn apply_shape(base: char, kind: VowelShape) -> Option<char> {
     let base_lower = lower_char(base);
     let is_upper = base.is_uppercase();
@@ -0,0 +1,212 @@
+use crate::{HistorySegment, VitypeEngine};
+
+// ==================== Edit Operations ====================
+
+/// One caret-relative edit. The caret starts after the old text and ends after
+/// the new text; `Delete` removes characters before the caret, like backspace.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum EditOp {
+    MoveLeft(usize),
+    Delete(usize),
+    Insert(String),
+    MoveRight(usize),
+}
+
+/// A run of the alignment between old and new text, from the left.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Run {
+    Keep(usize),
+    Change { removed: usize, inserted: usize },
+}
+
+// ==================== Helper Functions ====================
+
+/// Longest-common-subsequence alignment; words are short, so the quadratic
+/// table is cheap.
+fn align(old: &[char], new: &[char]) -> Vec<Run> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut runs: Vec<Run> = Vec::new();
+    let mut push = |run: Run| match (runs.last_mut(), run) {
+        (Some(Run::Keep(count)), Run::Keep(more)) => *count += more,
+        (
+            Some(Run::Change { removed, inserted }),
+            Run::Change {
+                removed: more_removed,
+                inserted: more_inserted,
+            },
+        ) => {
+            *removed += more_removed;
+            *inserted += more_inserted;
+        }
+        _ => runs.push(run),
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            push(Run::Keep(1));
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            push(Run::Change {
+                removed: 0,
+                inserted: 1,
+            });
+            j += 1;
+        } else {
+            push(Run::Change {
+                removed: 1,
+                inserted: 0,
+            });
+            i += 1;
+        }
+    }
+    runs
+}
+
+/// Applies changes from the rightmost one leftwards, so each `Delete` is a plain
+/// backspace at the caret, then returns the caret to the end.
+fn script_from_runs(runs: &[Run], new: &[char]) -> Vec<EditOp> {
+    let mut ops: Vec<EditOp> = Vec::new();
+    // Both measured from the end of the text, which edits further left never move.
+    let mut caret = 0;
+    let mut position = 0;
+    for run in runs.iter().rev() {
+        match *run {
+            Run::Keep(count) => position += count,
+            Run::Change { removed, inserted } => {
+                if position > caret {
+                    ops.push(EditOp::MoveLeft(position - caret));
+                    caret = position;
+                }
+                if removed > 0 {
+                    ops.push(EditOp::Delete(removed));
+                }
+                if inserted > 0 {
+                    let end = new.len() - position;
+                    ops.push(EditOp::Insert(new[end - inserted..end].iter().collect()));
+                }
+                position += inserted;
+            }
+        }
+    }
+    if caret > 0 {
+        ops.push(EditOp::MoveRight(caret));
+    }
+    ops
+}
+
+// ==================== Public Functions ====================
+
+/// The minimal caret-relative edit from `old` to `new`, with the caret after the
+/// text before and after.
+pub(crate) fn edit_script(old: &[char], new: &[char]) -> Vec<EditOp> {
+    script_from_runs(&align(old, new), new)
+}
+
+/// The simpler form: skip the common prefix and suffix and replace the middle.
+pub(crate) fn trimmed_edit_script(old: &[char], new: &[char]) -> Vec<EditOp> {
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let runs = [
+        Run::Keep(prefix),
+        Run::Change {
+            removed: old.len() - prefix - suffix,
+            inserted: new.len() - prefix - suffix,
+        },
+        Run::Keep(suffix),
+    ];
+    script_from_runs(&runs, new)
+}
+
+/// Like `edit_script`, but the caret starts at `old_caret` and must end at
+/// `new_caret`. Adjacent moves are merged.
+pub(crate) fn edit_script_at_caret(
+    old: &[char],
+    old_caret: usize,
+    new: &[char],
+    new_caret: usize,
+) -> Vec<EditOp> {
+    let mut ops: Vec<EditOp> = Vec::new();
+    let steps = std::iter::once(EditOp::MoveRight(old.len() - old_caret))
+        .chain(edit_script(old, new))
+        .chain(std::iter::once(EditOp::MoveLeft(new.len() - new_caret)));
+    for op in steps {
+        let offset = match op {
+            EditOp::MoveLeft(count) => -(count as isize),
+            EditOp::MoveRight(count) => count as isize,
+            other => {
+                ops.push(other);
+                continue;
+            }
+        };
+        let previous = match ops.last() {
+            Some(EditOp::MoveLeft(count)) => -(*count as isize),
+            Some(EditOp::MoveRight(count)) => *count as isize,
+            _ => 0,
+        };
+        if previous != 0 {
+            ops.pop();
+        }
+        match previous + offset {
+            0 => {}
+            net if net < 0 => ops.push(EditOp::MoveLeft(net.unsigned_abs())),
+            net => ops.push(EditOp::MoveRight(net as usize)),
+        }
+    }
+    ops
+}
+
+// ==================== Edit Script Methods on VitypeEngine ====================
+
+impl VitypeEngine {
+    /// Everything the engine can still rewrite: history followed by the current word.
+    pub(super) fn visible_text(&self) -> Vec<char> {
+        let mut text: Vec<char> = Vec::new();
+        for segment in &self.history {
+            match segment {
+                HistorySegment::Word(word) => text.extend(&word.buffer),
+                HistorySegment::Boundary(chars) => text.extend(chars),
+            }
+        }
+        text.extend(&self.buffer);
+        text
+    }
+
+    /// `process` returning an edit script instead of "delete N, insert text".
+    /// `None` means the host inserts the key itself, as with `process`.
+    pub(crate) fn process_edit_script(
+        &mut self,
+        input: &str,
+        trimmed: bool,
+    ) -> Option<Vec<EditOp>> {
+        let before = self.visible_text();
+        let action = self.process(input)?;
+        let old = &before[before.len() - action.delete_count.min(before.len())..];
+        let new: Vec<char> = action.text.chars().collect();
+        Some(if trimmed {
+            trimmed_edit_script(old, &new)
+        } else {
+            edit_script(old, &new)
+        })
+    }
+}
@@ -1,10 +1,26 @@
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
+use std::path::Path;
 use std::ptr;
 
 use unicode_normalization::UnicodeNormalization;
 
-use crate::common::{InputMethod, OutputEncoding, TonePlacement};
+use crate::collation::{compare, sort_key, CaseOrder, CollationOptions};
+use crate::common::{IYStyle, InputMethod, KeyTransformAction, OutputEncoding, TonePlacement};
+use crate::confusable::{Confusion, ConfusionKind};
+use crate::edit_script::EditOp;
+use crate::han_nom::HanNomTable;
+use crate::inventory::all_syllables;
+use crate::ipa::{transcribe, Dialect};
+use crate::lexicon::{Lexicon, DEFAULT_LEXICON};
+use crate::literal_context::LiteralContextOptions;
+use crate::loanword::{LoanwordLexicon, BUNDLED_LOANWORDS};
+use crate::normalize::{normalize_i_y, normalize_tone_placement};
+use crate::orthography::{OrthographyPack, CENTRAL_HIGHLANDS_PACK};
+use crate::preedit::PreeditUpdate;
+use crate::restore::restore_diacritics;
+use crate::session::SessionManager;
+use crate::syllable::SyllableError;
 use crate::VitypeEngine;
 
 #[repr(C)]
@@ -14,6 +30,61 @@ pub struct VitypeTransformResult {
     pub text: *mut c_char,
 }
 
+#[repr(C)]
+pub struct VitypePreeditResult {
+    pub handled: bool,
+    pub preedit: *mut c_char,
+    pub caret: i32,
+    pub commit: *mut c_char,
+}
+
+#[repr(C)]
+pub struct VitypeEditOp {
+    pub kind: i32,
+    pub count: i32,
+    pub text: *mut c_char,
+}
+
+#[repr(C)]
+pub struct VitypeEditScript {
+    pub has_action: bool,
+    pub ops: *mut VitypeEditOp,
+    pub count: i32,
+}
+
+#[repr(C)]
+pub struct VitypeTextIssue {
+    pub byte_start: i32,
+    pub byte_end: i32,
+    pub char_start: i32,
+    pub char_end: i32,
+    pub kind: i32,
+    pub suggestions: *mut c_char,
+}
+
+#[repr(C)]
+pub struct VitypeTextIssueList {
+    pub issues: *mut VitypeTextIssue,
+    pub count: i32,
+}
+
+#[repr(C)]
+pub struct VitypeRestoredToken {
+    pub byte_start: i32,
+    pub byte_end: i32,
+    pub char_start: i32,
+    pub char_end: i32,
+    pub text: *mut c_char,
+    pub confidence: f64,
+}
+
+#[repr(C)]
+pub struct VitypeRestoration {
+    pub text: *mut c_char,
+    pub tokens: *mut VitypeRestoredToken,
+    pub count: i32,
+}
+
 fn empty_result() -> VitypeTransformResult {
     VitypeTransformResult {
         has_action: false,
@@ -29,6 +100,75 @@ fn convert_to_output_encoding(text: String, encoding: OutputEncoding) -> String
     }
 }
 
+fn empty_issue_list() -> VitypeTextIssueList {
+    VitypeTextIssueList {
+        issues: ptr::null_mut(),
+        count: 0,
+    }
+}
+
+fn into_c_string(text: String) -> *mut c_char {
+    CString::new(text)
+        .unwrap_or_else(|_| CString::new("").unwrap())
+        .into_raw()
+}
+
+fn syllable_error_code(kind: SyllableError) -> i32 {
+    match kind {
+        SyllableError::InvalidCluster => 0,
+        SyllableError::InvalidOnset => 1,
+        SyllableError::InvalidCoda => 2,
+        SyllableError::StopFinalTone => 3,
+        SyllableError::MisplacedTone => 4,
+    }
+}
+
+fn confusion_kind_code(kind: ConfusionKind) -> i32 {
+    match kind {
+        ConfusionKind::HoiNga => 0,
+        ConfusionKind::ChTr => 1,
+        ConfusionKind::SX => 2,
+        ConfusionKind::DGiR => 3,
+        ConfusionKind::FinalNNg => 4,
+        ConfusionKind::FinalTC => 5,
+    }
+}
+
+fn confusions_to_issue_list(confusions: Vec<Confusion>) -> VitypeTextIssueList {
+    into_issue_list(
+        confusions
+            .into_iter()
+            .map(|confusion| VitypeTextIssue {
+                byte_start: confusion.byte_start as i32,
+                byte_end: confusion.byte_end as i32,
+                char_start: confusion.char_start as i32,
+                char_end: confusion.char_end as i32,
+                kind: confusion_kind_code(confusion.kind),
+                suggestions: into_c_string(confusion.suggestion),
+            })
+            .collect(),
+    )
+}
+
+fn into_issue_list(issues: Vec<VitypeTextIssue>) -> VitypeTextIssueList {
+    if issues.is_empty() {
+        return empty_issue_list();
+    }
+    let count = issues.len() as i32;
+    let boxed = issues.into_boxed_slice();
+    VitypeTextIssueList {
+        issues: Box::into_raw(boxed) as *mut VitypeTextIssue,
+        count,
+    }
+}
+
+unsafe fn str_from_c<'a>(text_utf8: *const c_char) -> Option<&'a str> {
+    if text_utf8.is_null() {
+        return None;
+    }
+    CStr::from_ptr(text_utf8).to_str().ok()
+}
+
 #[no_mangle]
 pub extern "C" fn vitype_engine_new() -> *mut VitypeEngine {
     Box::into_raw(Box::new(VitypeEngine::new()))
@@ -63,6 +203,99 @@ pub extern "C" fn vitype_engine_delete_last_character(engine: *mut VitypeEngine)
     }
 }
 
+/// Backspace that reports what the host should do: with smart backspace on, a
+/// result with an action redraws the word without its last mark; no action
+/// means the host deletes one char as usual.
+#[no_mangle]
+pub extern "C" fn vitype_engine_backspace(engine: *mut VitypeEngine) -> VitypeTransformResult {
+    if engine.is_null() {
+        return empty_result();
+    }
+    let (action, output_encoding) = unsafe {
+        (
+            (*engine).delete_last_character(),
+            (*engine).output_encoding(),
+        )
+    };
+    into_transform_result(action, output_encoding)
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_set_smart_backspace(engine: *mut VitypeEngine, enabled: bool) {
+    if engine.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).set_smart_backspace(enabled);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_undo_last_transform(
+    engine: *mut VitypeEngine,
+) -> VitypeTransformResult {
+    if engine.is_null() {
+        return empty_result();
+    }
+    let (action, output_encoding) =
+        unsafe { ((*engine).undo_last_transform(), (*engine).output_encoding()) };
+    into_transform_result(action, output_encoding)
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_toggle_raw_last_word(
+    engine: *mut VitypeEngine,
+) -> VitypeTransformResult {
+    if engine.is_null() {
+        return empty_result();
+    }
+    let (action, output_encoding) = unsafe {
+        (
+            (*engine).toggle_raw_last_word(),
+            (*engine).output_encoding(),
+        )
+    };
+    into_transform_result(action, output_encoding)
+}
+
+/// Replays the current word as Vietnamese even if it failed validation; with
+/// `remember`, the same keystrokes stay Vietnamese until the engine is freed.
+#[no_mangle]
+pub extern "C" fn vitype_engine_force_vietnamese(
+    engine: *mut VitypeEngine,
+    remember: bool,
+) -> VitypeTransformResult {
+    if engine.is_null() {
+        return empty_result();
+    }
+    let (action, output_encoding) = unsafe {
+        (
+            (*engine).force_vietnamese(remember),
+            (*engine).output_encoding(),
+        )
+    };
+    into_transform_result(action, output_encoding)
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_clear_forced_vietnamese_words(engine: *mut VitypeEngine) {
+    if engine.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).clear_forced_vietnamese_words();
+    }
+}
+
+/// Returns false when the keystroke provenance no longer matches the buffers.
+#[no_mangle]
+pub extern "C" fn vitype_engine_check_consistency(engine: *const VitypeEngine) -> bool {
+    if engine.is_null() {
+        return false;
+    }
+    unsafe { (*engine).check_provenance().is_ok() }
+}
+
 #[no_mangle]
 pub extern "C" fn vitype_engine_set_auto_fix_tone(engine: *mut VitypeEngine, enabled: bool) {
     if engine.is_null() {
@@ -83,6 +316,42 @@ pub extern "C" fn vitype_engine_set_free_tone_placement(engine: *mut VitypeEngin
     }
 }
 
+#[no_mangle]
+pub extern "C" fn vitype_engine_set_mixed_case_is_foreign(
+    engine: *mut VitypeEngine,
+    enabled: bool,
+) {
+    if engine.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).set_mixed_case_is_foreign(enabled);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_set_smart_numeric_context(
+    engine: *mut VitypeEngine,
+    enabled: bool,
+) {
+    if engine.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).set_smart_numeric_context(enabled);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_skip_numeric_context_for_word(engine: *mut VitypeEngine) {
+    if engine.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).skip_numeric_context_for_word();
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn vitype_engine_set_input_method(engine: *mut VitypeEngine, method: i32) {
     if engine.is_null() {
@@ -111,17 +380,143 @@ pub extern "C" fn vitype_engine_set_output_encoding(engine: *mut VitypeEngine, e
     }
 }
 
+fn tone_placement_from_code(placement: i32) -> TonePlacement {
+    match placement {
+        1 => TonePlacement::NucleusOnly,
+        _ => TonePlacement::Orthographic,
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn vitype_engine_set_tone_placement(engine: *mut VitypeEngine, placement: i32) {
     if engine.is_null() {
         return;
     }
     unsafe {
-        let tone_placement = match placement {
-            1 => TonePlacement::NucleusOnly,
-            _ => TonePlacement::Orthographic,
-        };
-        (*engine).set_tone_placement(tone_placement);
+        (*engine).set_tone_placement(tone_placement_from_code(placement));
+    }
+}
+
+fn i_y_style_from_code(style: i32) -> IYStyle {
+    match style {
+        1 => IYStyle::PreferI,
+        2 => IYStyle::PreferY,
+        _ => IYStyle::Unchanged,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_set_i_y_style(engine: *mut VitypeEngine, style: i32) {
+    if engine.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).set_i_y_style(i_y_style_from_code(style));
+    }
+}
+
+/// Adds a pack in the text format of `data/orthography/`; returns false when the
+/// text cannot be parsed.
+#[no_mangle]
+pub extern "C" fn vitype_engine_add_orthography_pack(
+    engine: *mut VitypeEngine,
+    pack_utf8: *const c_char,
+) -> bool {
+    if engine.is_null() {
+        return false;
+    }
+    let Some(pack) = (unsafe { str_from_c(pack_utf8) }).and_then(OrthographyPack::parse) else {
+        return false;
+    };
+    unsafe {
+        (*engine).add_orthography_pack(pack);
+    }
+    true
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_add_builtin_orthography_pack(engine: *mut VitypeEngine, pack: i32) {
+    if engine.is_null() {
+        return;
+    }
+    let pack = match pack {
+        0 => CENTRAL_HIGHLANDS_PACK.clone(),
+        _ => return,
+    };
+    unsafe {
+        (*engine).add_orthography_pack(pack);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_clear_orthography_packs(engine: *mut VitypeEngine) {
+    if engine.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).clear_orthography_packs();
+    }
+}
+
+/// Sets the loanword lexicon from text in the format of `data/loanwords.txt`;
+/// returns false when the text cannot be parsed.
+#[no_mangle]
+pub extern "C" fn vitype_engine_set_loanwords(
+    engine: *mut VitypeEngine,
+    lexicon_utf8: *const c_char,
+) -> bool {
+    if engine.is_null() {
+        return false;
+    }
+    let Some(lexicon) = (unsafe { str_from_c(lexicon_utf8) }).and_then(LoanwordLexicon::parse)
+    else {
+        return false;
+    };
+    unsafe {
+        (*engine).set_loanword_lexicon(lexicon);
+    }
+    true
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_use_bundled_loanwords(engine: *mut VitypeEngine) {
+    if engine.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).set_loanword_lexicon(BUNDLED_LOANWORDS.clone());
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_clear_loanwords(engine: *mut VitypeEngine) {
+    if engine.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).clear_loanword_lexicon();
+    }
+}
+
+/// Chooses which tokens stay literal until the next whitespace.
+#[no_mangle]
+pub extern "C" fn vitype_engine_set_literal_contexts(
+    engine: *mut VitypeEngine,
+    urls: bool,
+    emails: bool,
+    paths: bool,
+    identifiers: bool,
+) {
+    if engine.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).set_literal_context_options(LiteralContextOptions {
+            urls,
+            emails,
+            paths,
+            identifiers,
+        });
     }
 }
 
@@ -156,12 +551,839 @@ pub extern "C" fn vitype_engine_process(
     }
 }
 
+fn into_edit_script(ops: Option<Vec<EditOp>>, output_encoding: OutputEncoding) -> VitypeEditScript {
+    let Some(ops) = ops else {
+        return VitypeEditScript {
+            has_action: false,
+            ops: ptr::null_mut(),
+            count: 0,
+        };
+    };
+    let ops: Vec<VitypeEditOp> = ops
+        .into_iter()
+        .map(|op| {
+            let (kind, count, text) = match op {
+                EditOp::MoveLeft(count) => (0, count, None),
+                EditOp::Delete(count) => (1, count, None),
+                EditOp::Insert(text) => {
+                    let text = convert_to_output_encoding(text, output_encoding);
+                    (2, text.chars().count(), Some(text))
+                }
+                EditOp::MoveRight(count) => (3, count, None),
+            };
+            VitypeEditOp {
+                kind,
+                count: count as i32,
+                text: text.map_or(ptr::null_mut(), into_c_string),
+            }
+        })
+        .collect();
+    let count = ops.len() as i32;
+    VitypeEditScript {
+        has_action: true,
+        ops: Box::into_raw(ops.into_boxed_slice()) as *mut VitypeEditOp,
+        count,
+    }
+}
+
+/// Like `vitype_engine_process`, but the change is a list of caret moves, deletions
+/// and insertions. `trimmed` selects the common prefix/suffix form.
 #[no_mangle]
-pub extern "C" fn vitype_engine_free_string(text: *mut c_char) {
-    if text.is_null() {
+pub extern "C" fn vitype_engine_process_edit_script(
+    engine: *mut VitypeEngine,
+    input_utf8: *const c_char,
+    trimmed: bool,
+) -> VitypeEditScript {
+    if engine.is_null() {
+        return into_edit_script(None, OutputEncoding::Unicode);
+    }
+    let Some(input) = (unsafe { str_from_c(input_utf8) }) else {
+        return into_edit_script(None, OutputEncoding::Unicode);
+    };
+    let (ops, output_encoding) = unsafe {
+        (
+            (*engine).process_edit_script(input, trimmed),
+            (*engine).output_encoding(),
+        )
+    };
+    into_edit_script(ops, output_encoding)
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_set_history_word_limit(engine: *mut VitypeEngine, limit: i32) {
+    if engine.is_null() || limit < 1 {
         return;
     }
     unsafe {
-        drop(CString::from_raw(text));
+        (*engine).set_history_word_limit(limit as usize);
+    }
+}
+
+/// Rebuilds engine state from the text before the host's caret when the two
+/// disagree; returns whether anything was rebuilt.
+#[no_mangle]
+pub extern "C" fn vitype_engine_sync_surrounding_text(
+    engine: *mut VitypeEngine,
+    text_before_cursor_utf8: *const c_char,
+) -> bool {
+    if engine.is_null() {
+        return false;
+    }
+    let Some(text) = (unsafe { str_from_c(text_before_cursor_utf8) }) else {
+        return false;
+    };
+    unsafe { (*engine).sync_surrounding_text(text) }
+}
+
+/// Mirrors a caret move; returns how far the engine's caret actually moved.
+#[no_mangle]
+pub extern "C" fn vitype_engine_move_cursor(engine: *mut VitypeEngine, delta: i32) -> i32 {
+    if engine.is_null() {
+        return 0;
+    }
+    unsafe { (*engine).move_cursor(delta as isize) as i32 }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_process_at_caret(
+    engine: *mut VitypeEngine,
+    input_utf8: *const c_char,
+) -> VitypeEditScript {
+    if engine.is_null() {
+        return into_edit_script(None, OutputEncoding::Unicode);
+    }
+    let Some(input) = (unsafe { str_from_c(input_utf8) }) else {
+        return into_edit_script(None, OutputEncoding::Unicode);
+    };
+    let (ops, output_encoding) = unsafe {
+        (
+            (*engine).process_at_caret(input),
+            (*engine).output_encoding(),
+        )
+    };
+    into_edit_script(ops, output_encoding)
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_delete_forward(engine: *mut VitypeEngine) -> VitypeEditScript {
+    if engine.is_null() {
+        return into_edit_script(None, OutputEncoding::Unicode);
+    }
+    let (ops, output_encoding) =
+        unsafe { ((*engine).delete_forward(), (*engine).output_encoding()) };
+    into_edit_script(ops, output_encoding)
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_delete_backward(engine: *mut VitypeEngine) -> VitypeEditScript {
+    if engine.is_null() {
+        return into_edit_script(None, OutputEncoding::Unicode);
+    }
+    let (ops, output_encoding) =
+        unsafe { ((*engine).delete_backward(), (*engine).output_encoding()) };
+    into_edit_script(ops, output_encoding)
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_edit_script_free(script: VitypeEditScript) {
+    if script.ops.is_null() {
+        return;
+    }
+    unsafe {
+        let ops = Box::from_raw(ptr::slice_from_raw_parts_mut(
+            script.ops,
+            script.count as usize,
+        ));
+        for op in ops.iter() {
+            vitype_engine_free_string(op.text);
+        }
+    }
+}
+
+fn into_preedit_result(
+    update: Option<PreeditUpdate>,
+    output_encoding: OutputEncoding,
+) -> VitypePreeditResult {
+    let Some(update) = update else {
+        return VitypePreeditResult {
+            handled: false,
+            preedit: ptr::null_mut(),
+            caret: 0,
+            commit: ptr::null_mut(),
+        };
+    };
+    // The caret counts characters of the encoded preedit, so encode its prefix too.
+    let before_caret: String = update.preedit.chars().take(update.caret).collect();
+    let caret = convert_to_output_encoding(before_caret, output_encoding)
+        .chars()
+        .count();
+    VitypePreeditResult {
+        handled: true,
+        preedit: into_c_string(convert_to_output_encoding(update.preedit, output_encoding)),
+        caret: caret as i32,
+        commit: into_c_string(convert_to_output_encoding(update.commit, output_encoding)),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_process_preedit(
+    engine: *mut VitypeEngine,
+    input_utf8: *const c_char,
+) -> VitypePreeditResult {
+    if engine.is_null() {
+        return into_preedit_result(None, OutputEncoding::Unicode);
+    }
+    let Some(input) = (unsafe { str_from_c(input_utf8) }) else {
+        return into_preedit_result(None, OutputEncoding::Unicode);
+    };
+    let (update, output_encoding) = unsafe {
+        (
+            (*engine).process_preedit(input),
+            (*engine).output_encoding(),
+        )
+    };
+    into_preedit_result(Some(update), output_encoding)
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_commit_preedit(engine: *mut VitypeEngine) -> VitypePreeditResult {
+    if engine.is_null() {
+        return into_preedit_result(None, OutputEncoding::Unicode);
+    }
+    let (update, output_encoding) =
+        unsafe { ((*engine).commit_preedit(), (*engine).output_encoding()) };
+    into_preedit_result(Some(update), output_encoding)
+}
+
+/// `handled` is false when nothing is composed and the host should delete itself.
+#[no_mangle]
+pub extern "C" fn vitype_engine_delete_preedit_character(
+    engine: *mut VitypeEngine,
+) -> VitypePreeditResult {
+    if engine.is_null() {
+        return into_preedit_result(None, OutputEncoding::Unicode);
+    }
+    let (update, output_encoding) = unsafe {
+        (
+            (*engine).delete_preedit_character(),
+            (*engine).output_encoding(),
+        )
+    };
+    into_preedit_result(update, output_encoding)
+}
+
+fn into_transform_result(
+    action: Option<KeyTransformAction>,
+    output_encoding: OutputEncoding,
+) -> VitypeTransformResult {
+    match action {
+        Some(action) => VitypeTransformResult {
+            has_action: true,
+            delete_count: action.delete_count as i32,
+            text: into_c_string(convert_to_output_encoding(action.text, output_encoding)),
+        },
+        None => empty_result(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_set_han_nom_enabled(engine: *mut VitypeEngine, enabled: bool) {
+    if engine.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).set_han_nom_enabled(enabled);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_set_han_nom_page_size(engine: *mut VitypeEngine, page_size: i32) {
+    if engine.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).set_han_nom_page_size(page_size.max(1) as usize);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_load_han_nom_table(
+    engine: *mut VitypeEngine,
+    path_utf8: *const c_char,
+) -> bool {
+    if engine.is_null() {
+        return false;
+    }
+    let Some(path) = (unsafe { str_from_c(path_utf8) }) else {
+        return false;
+    };
+    match HanNomTable::load(Path::new(path)) {
+        Ok(table) => {
+            unsafe {
+                (*engine).set_han_nom_table(table);
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_open_han_nom_candidates(engine: *mut VitypeEngine) -> i32 {
+    if engine.is_null() {
+        return 0;
+    }
+    unsafe { (*engine).open_han_nom_candidates() as i32 }
+}
+
+/// Candidates on the current page, '\n'-separated; empty when no list is open.
+#[no_mangle]
+pub extern "C" fn vitype_engine_han_nom_candidates(engine: *const VitypeEngine) -> *mut c_char {
+    if engine.is_null() {
+        return ptr::null_mut();
+    }
+    let candidates = unsafe { (*engine).han_nom_candidates().join("\n") };
+    into_c_string(candidates)
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_han_nom_next_page(engine: *mut VitypeEngine) -> bool {
+    if engine.is_null() {
+        return false;
+    }
+    unsafe { (*engine).han_nom_next_page() }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_han_nom_previous_page(engine: *mut VitypeEngine) -> bool {
+    if engine.is_null() {
+        return false;
+    }
+    unsafe { (*engine).han_nom_previous_page() }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_han_nom_select(
+    engine: *mut VitypeEngine,
+    index: i32,
+) -> VitypeTransformResult {
+    if engine.is_null() || index < 0 {
+        return empty_result();
+    }
+    let (action, output_encoding) = unsafe {
+        (
+            (*engine).select_han_nom_candidate(index as usize),
+            (*engine).output_encoding(),
+        )
+    };
+    into_transform_result(action, output_encoding)
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_free_string(text: *mut c_char) {
+    if text.is_null() {
+        return;
+    }
+    unsafe {
+        drop(CString::from_raw(text));
+    }
+}
+
+/// Suggestions are joined with '\n', best first.
+#[no_mangle]
+pub extern "C" fn vitype_engine_check_text(
+    engine: *const VitypeEngine,
+    text_utf8: *const c_char,
+) -> VitypeTextIssueList {
+    if engine.is_null() {
+        return empty_issue_list();
+    }
+    let Some(text) = (unsafe { str_from_c(text_utf8) }) else {
+        return empty_issue_list();
+    };
+
+    let issues = unsafe { (*engine).check_text(text) };
+    into_issue_list(
+        issues
+            .into_iter()
+            .map(|issue| VitypeTextIssue {
+                byte_start: issue.byte_start as i32,
+                byte_end: issue.byte_end as i32,
+                char_start: issue.char_start as i32,
+                char_end: issue.char_end as i32,
+                kind: syllable_error_code(issue.kind),
+                suggestions: into_c_string(issue.suggestions.join("\n")),
+            })
+            .collect(),
+    )
+}
+
+/// Ranks with the bundled placeholder lexicon, whose counts are estimates.
+#[no_mangle]
+pub extern "C" fn vitype_engine_check_confusables(
+    engine: *const VitypeEngine,
+    text_utf8: *const c_char,
+) -> VitypeTextIssueList {
+    if engine.is_null() {
+        return empty_issue_list();
+    }
+    let Some(text) = (unsafe { str_from_c(text_utf8) }) else {
+        return empty_issue_list();
+    };
+    confusions_to_issue_list(unsafe { (*engine).check_confusables(text) })
+}
+
+/// Spans are relative to the text of the committed words still held in history.
+#[no_mangle]
+pub extern "C" fn vitype_engine_check_history_confusables(
+    engine: *const VitypeEngine,
+) -> VitypeTextIssueList {
+    if engine.is_null() {
+        return empty_issue_list();
+    }
+    confusions_to_issue_list(unsafe { (*engine).check_history_confusables() })
+}
+
+/// `kind` holds the detected input method (0 = Telex, 1 = VNI); the converted word
+/// is the only suggestion.
+#[no_mangle]
+pub extern "C" fn vitype_engine_find_unconverted_words(
+    engine: *const VitypeEngine,
+    text_utf8: *const c_char,
+) -> VitypeTextIssueList {
+    if engine.is_null() {
+        return empty_issue_list();
+    }
+    let Some(text) = (unsafe { str_from_c(text_utf8) }) else {
+        return empty_issue_list();
+    };
+
+    let hits = unsafe { (*engine).find_unconverted_words(text) };
+    into_issue_list(
+        hits.into_iter()
+            .map(|hit| VitypeTextIssue {
+                byte_start: hit.byte_start as i32,
+                byte_end: hit.byte_end as i32,
+                char_start: hit.char_start as i32,
+                char_end: hit.char_end as i32,
+                kind: hit.input_method as i32,
+                suggestions: into_c_string(hit.converted),
+            })
+            .collect(),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_text_issue_list_free(list: VitypeTextIssueList) {
+    if list.issues.is_null() {
+        return;
+    }
+    unsafe {
+        let issues = Box::from_raw(ptr::slice_from_raw_parts_mut(
+            list.issues,
+            list.count as usize,
+        ));
+        for issue in issues.iter() {
+            vitype_engine_free_string(issue.suggestions);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_lexicon_load(path_utf8: *const c_char) -> *mut Lexicon {
+    let Some(path) = (unsafe { str_from_c(path_utf8) }) else {
+        return ptr::null_mut();
+    };
+    match Lexicon::load(Path::new(path)) {
+        Ok(lexicon) => Box::into_raw(Box::new(lexicon)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_lexicon_new() -> *mut Lexicon {
+    Box::into_raw(Box::new(Lexicon::default()))
+}
+
+/// Adds the syllable counts of a UTF-8 corpus file to `lexicon`.
+#[no_mangle]
+pub extern "C" fn vitype_lexicon_train_file(
+    lexicon: *mut Lexicon,
+    path_utf8: *const c_char,
+) -> bool {
+    if lexicon.is_null() {
+        return false;
+    }
+    let Some(path) = (unsafe { str_from_c(path_utf8) }) else {
+        return false;
+    };
+    unsafe { (*lexicon).train_from_file(Path::new(path)).is_ok() }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_lexicon_save(lexicon: *const Lexicon, path_utf8: *const c_char) -> bool {
+    if lexicon.is_null() {
+        return false;
+    }
+    let Some(path) = (unsafe { str_from_c(path_utf8) }) else {
+        return false;
+    };
+    unsafe { (*lexicon).save(Path::new(path)).is_ok() }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_lexicon_free(lexicon: *mut Lexicon) {
+    if !lexicon.is_null() {
+        unsafe {
+            drop(Box::from_raw(lexicon));
+        }
+    }
+}
+
+/// Passing a null `lexicon` uses the bundled default.
+#[no_mangle]
+pub extern "C" fn vitype_engine_restore_diacritics(
+    engine: *const VitypeEngine,
+    lexicon: *const Lexicon,
+    text_utf8: *const c_char,
+) -> VitypeRestoration {
+    let empty = VitypeRestoration {
+        text: ptr::null_mut(),
+        tokens: ptr::null_mut(),
+        count: 0,
+    };
+    if engine.is_null() {
+        return empty;
+    }
+    let Some(text) = (unsafe { str_from_c(text_utf8) }) else {
+        return empty;
+    };
+    let lexicon = if lexicon.is_null() {
+        &*DEFAULT_LEXICON
+    } else {
+        unsafe { &*lexicon }
+    };
+
+    let restoration = unsafe { restore_diacritics(text, lexicon, (*engine).tone_placement) };
+    let tokens: Vec<VitypeRestoredToken> = restoration
+        .tokens
+        .into_iter()
+        .map(|token| VitypeRestoredToken {
+            byte_start: token.byte_start as i32,
+            byte_end: token.byte_end as i32,
+            char_start: token.char_start as i32,
+            char_end: token.char_end as i32,
+            text: into_c_string(token.text),
+            confidence: token.confidence,
+        })
+        .collect();
+    let count = tokens.len() as i32;
+    VitypeRestoration {
+        text: into_c_string(restoration.text),
+        tokens: Box::into_raw(tokens.into_boxed_slice()) as *mut VitypeRestoredToken,
+        count,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_restoration_free(restoration: VitypeRestoration) {
+    vitype_engine_free_string(restoration.text);
+    if restoration.tokens.is_null() {
+        return;
+    }
+    unsafe {
+        let tokens = Box::from_raw(ptr::slice_from_raw_parts_mut(
+            restoration.tokens,
+            restoration.count as usize,
+        ));
+        for token in tokens.iter() {
+            vitype_engine_free_string(token.text);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_normalize_tone_placement(
+    text_utf8: *const c_char,
+    placement: i32,
+) -> *mut c_char {
+    let Some(text) = (unsafe { str_from_c(text_utf8) }) else {
+        return ptr::null_mut();
+    };
+    into_c_string(normalize_tone_placement(
+        text,
+        tone_placement_from_code(placement),
+    ))
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_normalize_i_y(text_utf8: *const c_char, style: i32) -> *mut c_char {
+    let Some(text) = (unsafe { str_from_c(text_utf8) }) else {
+        return ptr::null_mut();
+    };
+    into_c_string(normalize_i_y(text, i_y_style_from_code(style)))
+}
+
+fn collation_options(case_order: i32, digraphs_as_units: bool) -> CollationOptions {
+    CollationOptions {
+        case_order: match case_order {
+            1 => CaseOrder::LowerFirst,
+            2 => CaseOrder::UpperFirst,
+            _ => CaseOrder::Ignore,
+        },
+        digraphs_as_units,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_collation_compare(
+    a_utf8: *const c_char,
+    b_utf8: *const c_char,
+    case_order: i32,
+    digraphs_as_units: bool,
+) -> i32 {
+    let (Some(a), Some(b)) = (unsafe { str_from_c(a_utf8) }, unsafe { str_from_c(b_utf8) }) else {
+        return 0;
+    };
+    compare(a, b, collation_options(case_order, digraphs_as_units)) as i32
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_collation_sort_key(
+    text_utf8: *const c_char,
+    case_order: i32,
+    digraphs_as_units: bool,
+    out_length: *mut usize,
+) -> *mut u8 {
+    if out_length.is_null() {
+        return ptr::null_mut();
+    }
+    let Some(text) = (unsafe { str_from_c(text_utf8) }) else {
+        unsafe { *out_length = 0 };
+        return ptr::null_mut();
+    };
+    let key = sort_key(text, collation_options(case_order, digraphs_as_units)).into_boxed_slice();
+    unsafe { *out_length = key.len() };
+    Box::into_raw(key) as *mut u8
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_collation_sort_key_free(key: *mut u8, length: usize) {
+    if key.is_null() {
+        return;
+    }
+    unsafe {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(key, length)));
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_transcribe_ipa(text_utf8: *const c_char, dialect: i32) -> *mut c_char {
+    let Some(text) = (unsafe { str_from_c(text_utf8) }) else {
+        return ptr::null_mut();
+    };
+    let dialect = match dialect {
+        1 => Dialect::Saigon,
+        _ => Dialect::Hanoi,
+    };
+    into_c_string(transcribe(text, dialect))
+}
+
+/// Every valid syllable, '\n'-separated; each spelling appears once even when
+/// both tone placements produce it.
+#[no_mangle]
+pub extern "C" fn vitype_all_syllables() -> *mut c_char {
+    let mut seen = std::collections::HashSet::new();
+    let words: Vec<String> = all_syllables()
+        .map(|entry| entry.text)
+        .filter(|text| seen.insert(text.clone()))
+        .collect();
+    into_c_string(words.join("\n"))
+}
+
+/// Copies `lexicon` into the engine as its prediction model.
+#[no_mangle]
+pub extern "C" fn vitype_engine_set_prediction_lexicon(
+    engine: *mut VitypeEngine,
+    lexicon: *const Lexicon,
+) {
+    if engine.is_null() || lexicon.is_null() {
+        return;
+    }
+    unsafe {
+        (*engine).set_prediction_model((*lexicon).clone());
+    }
+}
+
+/// Up to `limit` suggestions, '\n'-separated, best first.
+#[no_mangle]
+pub extern "C" fn vitype_engine_predictions(engine: *mut VitypeEngine, limit: i32) -> *mut c_char {
+    if engine.is_null() {
+        return ptr::null_mut();
+    }
+    let predictions = unsafe { (*engine).predictions(limit.max(0) as usize) };
+    let texts: Vec<&str> = predictions
+        .iter()
+        .map(|prediction| prediction.text.as_str())
+        .collect();
+    into_c_string(texts.join("\n"))
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_accept_prediction(
+    engine: *mut VitypeEngine,
+    index: i32,
+) -> VitypeTransformResult {
+    if engine.is_null() || index < 0 {
+        return empty_result();
+    }
+    let (action, output_encoding) = unsafe {
+        (
+            (*engine).accept_prediction(index as usize),
+            (*engine).output_encoding(),
+        )
+    };
+    into_transform_result(action, output_encoding)
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_engine_reject_prediction(
+    engine: *mut VitypeEngine,
+    index: i32,
+) -> VitypeTransformResult {
+    if engine.is_null() || index < 0 {
+        return empty_result();
+    }
+    let (action, output_encoding) = unsafe {
+        (
+            (*engine).reject_prediction(index as usize),
+            (*engine).output_encoding(),
+        )
+    };
+    into_transform_result(action, output_encoding)
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_session_manager_new() -> *mut SessionManager {
+    Box::into_raw(Box::new(SessionManager::new()))
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_session_manager_free(manager: *mut SessionManager) {
+    if !manager.is_null() {
+        unsafe {
+            drop(Box::from_raw(manager));
+        }
+    }
+}
+
+/// The engine that holds the shared settings; configure it with the usual
+/// `vitype_engine_set_*` calls, then call `vitype_session_manager_apply_configuration`.
+/// The manager cannot see writes through this pointer, so no session picks them
+/// up until that call.
+#[no_mangle]
+pub extern "C" fn vitype_session_manager_configuration(
+    manager: *mut SessionManager,
+) -> *mut VitypeEngine {
+    if manager.is_null() {
+        return std::ptr::null_mut();
+    }
+    unsafe { (*manager).configuration_mut() }
+}
+
+/// Required after every settings change through the configuration engine: pushes
+/// the settings to the active session and to the others when they are activated.
+#[no_mangle]
+pub extern "C" fn vitype_session_manager_apply_configuration(manager: *mut SessionManager) {
+    if manager.is_null() {
+        return;
+    }
+    unsafe {
+        (*manager).apply_configuration();
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_session_manager_set_max_sessions(
+    manager: *mut SessionManager,
+    limit: i32,
+) {
+    if manager.is_null() || limit < 1 {
+        return;
+    }
+    unsafe {
+        (*manager).set_max_sessions(limit as usize);
+    }
+}
+
+/// Returns the engine for `context_utf8`, creating the session if needed. The
+/// pointer stays valid until that session is dropped or evicted.
+#[no_mangle]
+pub extern "C" fn vitype_session_manager_activate(
+    manager: *mut SessionManager,
+    context_utf8: *const c_char,
+) -> *mut VitypeEngine {
+    if manager.is_null() {
+        return std::ptr::null_mut();
+    }
+    let Some(context_id) = (unsafe { str_from_c(context_utf8) }) else {
+        return std::ptr::null_mut();
+    };
+    unsafe { (*manager).activate(context_id) }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_session_manager_deactivate(manager: *mut SessionManager) {
+    if manager.is_null() {
+        return;
+    }
+    unsafe {
+        (*manager).deactivate();
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_session_manager_active_engine(
+    manager: *mut SessionManager,
+) -> *mut VitypeEngine {
+    if manager.is_null() {
+        return std::ptr::null_mut();
+    }
+    unsafe {
+        (*manager)
+            .active_engine_mut()
+            .map_or(std::ptr::null_mut(), |engine| engine as *mut VitypeEngine)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_session_manager_drop(
+    manager: *mut SessionManager,
+    context_utf8: *const c_char,
+) -> bool {
+    if manager.is_null() {
+        return false;
+    }
+    let Some(context_id) = (unsafe { str_from_c(context_utf8) }) else {
+        return false;
+    };
+    unsafe { (*manager).drop_session(context_id) }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_session_manager_drop_all(manager: *mut SessionManager) {
+    if manager.is_null() {
+        return;
+    }
+    unsafe {
+        (*manager).drop_all_sessions();
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn vitype_session_manager_session_count(manager: *const SessionManager) -> i32 {
+    if manager.is_null() {
+        return 0;
     }
+    unsafe { (*manager).session_count() as i32 }
 }
@@ -0,0 +1,100 @@
+use crate::common::{lower_string, KeyTransformAction};
+use crate::VitypeEngine;
+
+// ==================== Force Vietnamese ====================
+//
+// A word that failed validation (a non-standard name, a dialect spelling) keeps
+// typing literally. Forcing it replays `raw_buffer` with validation off: the
+// foreign fallback is skipped and tones go on any vowel cluster, as with
+// `free_tone_placement`. Remembered words stay forced whenever their keystrokes
+// are typed again in the same session: any prefix is forced while typing, but
+// only the exact word is kept at commit.
+
+impl VitypeEngine {
+    /// Reinterprets the current word as Vietnamese and returns the rewrite.
+    /// With `remember`, the same keystrokes stay Vietnamese for the session.
+    pub(crate) fn force_vietnamese(&mut self, remember: bool) -> Option<KeyTransformAction> {
+        if self.buffer.is_empty() || self.is_caret_inside_word() {
+            return None;
+        }
+        self.dismiss_han_nom_candidates();
+        self.clear_predictions();
+        if remember {
+            self.forced_vietnamese_words
+                .insert(lower_string(&self.raw_buffer));
+        }
+        self.vietnamese_forced = true;
+
+        let before = self.buffer.clone();
+        let rebuilt = self.rebuild_current_word_from_raw(&self.raw_buffer);
+        self.adopt_current_word_state_from(rebuilt);
+        self.repair_provenance_if_needed();
+
+        let start = before
+            .iter()
+            .zip(&self.buffer)
+            .take_while(|(old, new)| old == new)
+            .count();
+        if start == before.len() && start == self.buffer.len() {
+            return None;
+        }
+        Some(KeyTransformAction {
+            delete_count: before.len() - start,
+            text: self.buffer_string_from(start),
+        })
+    }
+
+    /// Forgets the words remembered by `force_vietnamese`.
+    pub(crate) fn clear_forced_vietnamese_words(&mut self) {
+        self.forced_vietnamese_words.clear();
+    }
+
+    /// Sends a word that was forced only as a prefix of a remembered word back to
+    /// its keystrokes at commit, unless it is valid on its own. Like the i/y
+    /// restyle, the action also carries the boundary character.
+    pub(super) fn revert_remembered_prefix_before_commit(
+        &mut self,
+        boundary: char,
+    ) -> Option<KeyTransformAction> {
+        if self.vietnamese_forced
+            || self.is_foreign_mode
+            || self.transforms_locked
+            || !self.is_vietnamese_forced()
+            || self
+                .forced_vietnamese_words
+                .contains(&lower_string(&self.raw_buffer))
+            || !self.is_foreign_spelling()
+            || self.loanword_keeps_vietnamese()
+        {
+            return None;
+        }
+
+        let delete_count = self.buffer.len();
+        let needs_visible_rewrite = self.buffer != self.raw_buffer;
+        self.buffer = self.raw_buffer.clone();
+        self.is_foreign_mode = true;
+        self.clear_transform_state();
+        self.reset_provenance();
+        if !needs_visible_rewrite {
+            return None;
+        }
+        let mut text: String = self.raw_buffer.iter().collect();
+        text.push(boundary);
+        Some(KeyTransformAction { delete_count, text })
+    }
+
+    /// Whether validation is off for the current word, either because it was
+    /// forced or because it spells a prefix of a remembered word.
+    pub(super) fn is_vietnamese_forced(&self) -> bool {
+        if self.vietnamese_forced {
+            return true;
+        }
+        if self.forced_vietnamese_words.is_empty() || self.raw_buffer.is_empty() {
+            return false;
+        }
+        let raw = lower_string(&self.raw_buffer);
+        self.forced_vietnamese_words
+            .iter()
+            .any(|word| word.starts_with(&raw))
+    }
+}
@@ -0,0 +1,240 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::common::{KeyTransformAction, TonePlacement};
+use crate::normalize::normalize_tone_placement;
+use crate::provenance::whole_word_provenance;
+use crate::{HistorySegment, VitypeEngine};
+
+const DEFAULT_PAGE_SIZE: usize = 9;
+
+// ==================== Lookup Table ====================
+
+/// Ranked Chữ Hán / Chữ Nôm candidates keyed by lowercase syllable.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct HanNomTable {
+    entries: HashMap<String, Vec<String>>,
+}
+
+fn lookup_key(syllable: &str) -> String {
+    normalize_tone_placement(&syllable.to_lowercase(), TonePlacement::Orthographic)
+}
+
+impl HanNomTable {
+    /// Parses `syllable<TAB>candidate candidate …`, best candidate first. Lines
+    /// starting with `#` are comments; repeated syllables append candidates.
+    pub(crate) fn parse(text: &str) -> HanNomTable {
+        let mut table = HanNomTable::default();
+        for line in text.lines() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((syllable, candidates)) = line.split_once('\t') else {
+                continue;
+            };
+            let entry = table
+                .entries
+                .entry(lookup_key(syllable.trim()))
+                .or_default();
+            for candidate in candidates.split_whitespace() {
+                if !entry.iter().any(|existing| existing == candidate) {
+                    entry.push(candidate.to_string());
+                }
+            }
+        }
+        table
+    }
+
+    pub(crate) fn load(path: &Path) -> io::Result<HanNomTable> {
+        Ok(HanNomTable::parse(&std::fs::read_to_string(path)?))
+    }
+
+    pub(crate) fn lookup(&self, syllable: &str) -> &[String] {
+        self.entries
+            .get(&lookup_key(syllable))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+}
+
+pub(crate) static DEFAULT_HAN_NOM_TABLE: Lazy<HanNomTable> =
+    Lazy::new(|| HanNomTable::parse(include_str!("../data/han_nom.txt")));
+
+// ==================== Candidate State ====================
+
+/// An open candidate list for one word. `trailing` is the boundary text typed after
+/// a committed word; it is re-emitted after the selected character.
+#[derive(Clone, Debug)]
+struct CandidateSession {
+    candidates: Vec<String>,
+    page: usize,
+    word_length: usize,
+    trailing: String,
+    is_committed: bool,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct HanNomState {
+    enabled: bool,
+    page_size: usize,
+    table: Option<Arc<HanNomTable>>,
+    session: Option<CandidateSession>,
+}
+
+impl Default for HanNomState {
+    fn default() -> Self {
+        HanNomState {
+            enabled: false,
+            page_size: DEFAULT_PAGE_SIZE,
+            table: None,
+            session: None,
+        }
+    }
+}
+
+// ==================== Candidate Methods on VitypeEngine ====================
+
+impl VitypeEngine {
+    /// With Hán-Nôm mode on, committing a word opens its candidate list.
+    pub(crate) fn set_han_nom_enabled(&mut self, enabled: bool) {
+        self.han_nom.enabled = enabled;
+        self.han_nom.session = None;
+    }
+
+    pub(crate) fn set_han_nom_page_size(&mut self, page_size: usize) {
+        self.han_nom.page_size = page_size.max(1);
+    }
+
+    /// Replaces the bundled table with one loaded from disk.
+    pub(crate) fn set_han_nom_table(&mut self, table: HanNomTable) {
+        self.han_nom.table = Some(Arc::new(table));
+    }
+
+    /// Takes over the mode, page size and table, but not the open candidates.
+    pub(super) fn copy_han_nom_settings_from(&mut self, other: &VitypeEngine) {
+        self.han_nom.enabled = other.han_nom.enabled;
+        self.han_nom.page_size = other.han_nom.page_size;
+        self.han_nom.table = other.han_nom.table.clone();
+    }
+
+    fn han_nom_lookup(&self, syllable: &str) -> Vec<String> {
+        let table = self
+            .han_nom
+            .table
+            .as_deref()
+            .unwrap_or(&DEFAULT_HAN_NOM_TABLE);
+        table.lookup(syllable).to_vec()
+    }
+
+    pub(super) fn dismiss_han_nom_candidates(&mut self) {
+        self.han_nom.session = None;
+    }
+
+    /// Called after a word and its boundary have been committed to history.
+    pub(super) fn open_han_nom_after_commit(&mut self) {
+        if self.han_nom.enabled {
+            self.open_han_nom_candidates();
+        }
+    }
+
+    /// Opens candidates for the word being typed, or else for the last committed
+    /// word and the boundary after it. Returns the total number of candidates.
+    pub(crate) fn open_han_nom_candidates(&mut self) -> usize {
+        self.han_nom.session = None;
+        let (word, trailing, is_committed) = if !self.buffer.is_empty() {
+            (self.buffer.clone(), String::new(), false)
+        } else {
+            let mut segments = self.history.iter().rev();
+            let (Some(HistorySegment::Boundary(boundary)), Some(HistorySegment::Word(word))) =
+                (segments.next(), segments.next())
+            else {
+                return 0;
+            };
+            (word.buffer.clone(), boundary.iter().collect(), true)
+        };
+
+        let syllable: String = word.iter().collect();
+        let candidates = self.han_nom_lookup(&syllable);
+        if candidates.is_empty() {
+            return 0;
+        }
+        let count = candidates.len();
+        self.han_nom.session = Some(CandidateSession {
+            candidates,
+            page: 0,
+            word_length: word.len(),
+            trailing,
+            is_committed,
+        });
+        count
+    }
+
+    /// The candidates on the current page.
+    pub(crate) fn han_nom_candidates(&self) -> &[String] {
+        let Some(session) = &self.han_nom.session else {
+            return &[];
+        };
+        let start = session.page * self.han_nom.page_size;
+        let end = (start + self.han_nom.page_size).min(session.candidates.len());
+        &session.candidates[start..end]
+    }
+
+    pub(crate) fn han_nom_next_page(&mut self) -> bool {
+        let page_size = self.han_nom.page_size;
+        let Some(session) = &mut self.han_nom.session else {
+            return false;
+        };
+        if (session.page + 1) * page_size >= session.candidates.len() {
+            return false;
+        }
+        session.page += 1;
+        true
+    }
+
+    pub(crate) fn han_nom_previous_page(&mut self) -> bool {
+        let Some(session) = &mut self.han_nom.session else {
+            return false;
+        };
+        if session.page == 0 {
+            return false;
+        }
+        session.page -= 1;
+        true
+    }
+
+    /// Replaces the word with the candidate at `index` on the current page and
+    /// closes the list. The word keeps its raw keystrokes but is locked from
+    /// further transforms.
+    pub(crate) fn select_han_nom_candidate(&mut self, index: usize) -> Option<KeyTransformAction> {
+        let candidate = self.han_nom_candidates().get(index)?.clone();
+        let session = self.han_nom.session.take()?;
+        let chars: Vec<char> = candidate.chars().collect();
+
+        if session.is_committed {
+            let word = self
+                .history
+                .iter_mut()
+                .rev()
+                .find_map(|segment| match segment {
+                    HistorySegment::Word(word) => Some(word),
+                    HistorySegment::Boundary(_) => None,
+                })?;
+            word.provenance = whole_word_provenance(chars.len(), word.raw_buffer.len());
+            word.buffer = chars;
+            word.transforms_locked = true;
+        } else {
+            self.provenance = whole_word_provenance(chars.len(), self.raw_buffer.len());
+            self.word_snapshots.clear();
+            self.buffer = chars;
+            self.transforms_locked = true;
+        }
+
+        Some(KeyTransformAction {
+            delete_count: session.word_length + session.trailing.chars().count(),
+            text: candidate + &session.trailing,
+        })
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashSet;
+
+use crate::common::TonePlacement;
+use crate::diacritics::apply_tone;
+use crate::syllable::{compose_syllable, is_stop_coda, CodaRule, CODAS, NUCLEI, ONSETS, TONE_KEYS};
+use crate::VitypeEngine;
+
+const STOP_TONE_KEYS: [Option<char>; 3] = [None, Some('s'), Some('j')];
+
+// ==================== Syllable Entry ====================
+
+/// One written syllable; `tone` is the internal Telex tone key. A `NucleusOnly`
+/// entry is only produced when its spelling differs from the orthographic one.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SyllableEntry {
+    pub(crate) text: String,
+    pub(crate) onset: &'static str,
+    pub(crate) nucleus: &'static str,
+    pub(crate) coda: &'static str,
+    pub(crate) tone: Option<char>,
+    pub(crate) tone_placement: TonePlacement,
+}
+
+// ==================== Helper Functions ====================
+
+fn is_cluster_allowed(nucleus: &str) -> bool {
+    let cluster: Option<Vec<char>> = nucleus
+        .chars()
+        .map(VitypeEngine::normalize_vowel_for_cluster)
+        .collect();
+    match cluster {
+        Some(cluster) if cluster.len() > 1 => VitypeEngine::is_valid_vowel_cluster_chars(&cluster),
+        Some(_) => true,
+        None => false,
+    }
+}
+
+fn is_coda_allowed(coda: &str) -> bool {
+    coda.is_empty() || VitypeEngine::is_allowed_final_consonant(coda)
+}
+
+/// All toned spellings of one onset + nucleus + coda, or nothing when the bare
+/// spelling is invalid. The tone target does not depend on the tone, so each
+/// placement is resolved once and every tone is applied there.
+fn toned_entries(
+    onset: &'static str,
+    nucleus: &'static str,
+    coda: &'static str,
+) -> Vec<SyllableEntry> {
+    let nucleus_chars: Vec<char> = nucleus.chars().collect();
+    let Some(bare) = compose_syllable(
+        onset,
+        &nucleus_chars,
+        coda,
+        None,
+        TonePlacement::Orthographic,
+    ) else {
+        return Vec::new();
+    };
+    let bare: Vec<char> = bare.chars().collect();
+    let tones: &[Option<char>] = if is_stop_coda(coda) {
+        &STOP_TONE_KEYS
+    } else {
+        &TONE_KEYS
+    };
+
+    let targets: Vec<(TonePlacement, Option<usize>)> =
+        [TonePlacement::Orthographic, TonePlacement::NucleusOnly]
+            .iter()
+            .map(|placement| {
+                let target = VitypeEngine::for_word(&bare, *placement).tone_target_index();
+                (*placement, target)
+            })
+            .collect();
+
+    let mut entries = Vec::new();
+    for tone in tones {
+        let mut orthographic_text: Option<String> = None;
+        for (tone_placement, target) in &targets {
+            let mut chars = bare.clone();
+            if tone.is_some() {
+                let Some(index) = target else {
+                    continue;
+                };
+                let Some(toned) = apply_tone(chars[*index], *tone) else {
+                    continue;
+                };
+                chars[*index] = toned;
+            }
+            let text: String = chars.into_iter().collect();
+            if orthographic_text.as_ref() == Some(&text) {
+                continue;
+            }
+            if *tone_placement == TonePlacement::Orthographic {
+                orthographic_text = Some(text.clone());
+            }
+            entries.push(SyllableEntry {
+                text,
+                onset,
+                nucleus,
+                coda,
+                tone: *tone,
+                tone_placement: *tone_placement,
+            });
+        }
+    }
+    entries
+}
+
+// ==================== Public Functions ====================
+
+/// Every lowercase syllable the engine accepts, in every tone and both tone
+/// placements, generated from the onset, cluster and final-consonant tables.
+pub(crate) fn all_syllables() -> impl Iterator<Item = SyllableEntry> {
+    let mut seen: HashSet<(String, TonePlacement)> = HashSet::new();
+    ONSETS
+        .iter()
+        .flat_map(|onset| {
+            NUCLEI
+                .iter()
+                .filter(|(nucleus, _)| is_cluster_allowed(nucleus))
+                .flat_map(move |(nucleus, rule)| {
+                    CODAS
+                        .iter()
+                        .filter(move |coda| match rule {
+                            CodaRule::Required => !coda.is_empty() && is_coda_allowed(coda),
+                            CodaRule::Forbidden => coda.is_empty(),
+                            CodaRule::Optional => is_coda_allowed(coda),
+                        })
+                        .map(move |coda| (*onset, *nucleus, *coda))
+                })
+        })
+        .flat_map(|(onset, nucleus, coda)| toned_entries(onset, nucleus, coda))
+        // "giêng" is both g + iê and gi + ê; keep the first split only.
+        .filter(move |entry| seen.insert((entry.text.clone(), entry.tone_placement)))
+}
@@ -0,0 +1,271 @@
+use crate::common::TonePlacement;
+use crate::syllable::{is_stop_coda, is_vietnamese_letter, word_tokens, SyllableParts};
+use crate::VitypeEngine;
+
+// ==================== Dialect ====================
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub(crate) enum Dialect {
+    #[default]
+    Hanoi = 0,
+    Saigon = 1,
+}
+
+/// One syllable in IPA, split the same way the engine splits written syllables.
+/// The tone is written with Chao tone letters; glottalisation is marked with ˀ.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct IpaSyllable {
+    pub(crate) onset: &'static str,
+    pub(crate) glide: &'static str,
+    pub(crate) nucleus: &'static str,
+    pub(crate) coda: &'static str,
+    pub(crate) tone: &'static str,
+}
+
+impl IpaSyllable {
+    pub(crate) fn to_ipa(&self) -> String {
+        [self.onset, self.glide, self.nucleus, self.coda, self.tone].concat()
+    }
+}
+
+// ==================== Helper Functions ====================
+
+fn onset_ipa(onset: &str, dialect: Dialect) -> Option<&'static str> {
+    let saigon = dialect == Dialect::Saigon;
+    Some(match onset {
+        "" => "",
+        "b" => "ɓ",
+        "c" | "k" => "k",
+        "qu" => {
+            if saigon {
+                "w"
+            } else {
+                "k"
+            }
+        }
+        "ch" => "c",
+        "d" | "gi" => {
+            if saigon {
+                "j"
+            } else {
+                "z"
+            }
+        }
+        "r" => {
+            if saigon {
+                "ɹ"
+            } else {
+                "z"
+            }
+        }
+        "v" => {
+            if saigon {
+                "j"
+            } else {
+                "v"
+            }
+        }
+        "s" => {
+            if saigon {
+                "ʂ"
+            } else {
+                "s"
+            }
+        }
+        "tr" => {
+            if saigon {
+                "ʈ"
+            } else {
+                "c"
+            }
+        }
+        "đ" => "ɗ",
+        "g" | "gh" => "ɣ",
+        "h" => "h",
+        "kh" => "x",
+        "l" => "l",
+        "m" => "m",
+        "n" => "n",
+        "ng" | "ngh" => "ŋ",
+        "nh" => "ɲ",
+        "p" => "p",
+        "ph" => "f",
+        "t" => "t",
+        "th" => "tʰ",
+        "x" => "s",
+        _ => return None,
+    })
+}
+
+/// Splits a leading o/u that acts as the labial glide ("hoa", "thuyền", "huế").
+fn split_glide(nucleus: &str) -> (bool, &str) {
+    let mut chars = nucleus.chars();
+    let first = chars.next();
+    let rest = chars.as_str();
+    let second = rest.chars().next();
+    match (first, second) {
+        (Some('o'), Some('a' | 'ă' | 'e')) | (Some('u'), Some('â' | 'ê' | 'y' | 'ơ')) => {
+            (true, rest)
+        }
+        _ => (false, nucleus),
+    }
+}
+
+fn nucleus_ipa(core: &str, coda: &str, dialect: Dialect) -> Option<&'static str> {
+    Some(match core {
+        "a" if matches!(coda, "nh" | "ch") => {
+            if dialect == Dialect::Saigon {
+                "ă"
+            } else {
+                "ɛ"
+            }
+        }
+        "a" => "aː",
+        "ă" => "ă",
+        "â" => "ə̆",
+        "e" => "ɛ",
+        "ê" => "e",
+        "i" | "y" => "i",
+        "o" => "ɔ",
+        "ô" => "o",
+        "ơ" => "əː",
+        "u" => "u",
+        "ư" => "ɨ",
+        "ia" | "iê" | "yê" | "ya" => "iə",
+        "ua" | "uô" => "uə",
+        "ưa" | "ươ" => "ɨə",
+        "ai" => "aːj",
+        "ay" => "ăj",
+        "ao" => "aːw",
+        "au" => "ăw",
+        "âu" => "ə̆w",
+        "ây" => "ə̆j",
+        "eo" => "ɛw",
+        "êu" => "ew",
+        "iu" => "iw",
+        "oi" => "ɔj",
+        "ôi" => "oj",
+        "ơi" => "əːj",
+        "ui" => "uj",
+        "ưi" => "ɨj",
+        "ưu" => "ɨw",
+        "iêu" | "yêu" => "iəw",
+        "uôi" => "uəj",
+        "ươi" => "ɨəj",
+        "ươu" => "ɨəw",
+        _ => return None,
+    })
+}
+
+fn coda_ipa(coda: &str, core: &str, dialect: Dialect) -> Option<&'static str> {
+    // Saigon backs final n/t except after the front vowels i and ê.
+    let keeps_alveolar = matches!(core, "i" | "y" | "ê");
+    let saigon = dialect == Dialect::Saigon;
+    Some(match coda {
+        "" => "",
+        "c" | "k" => "k",
+        "ch" => {
+            if saigon {
+                "t"
+            } else {
+                "c"
+            }
+        }
+        "m" => "m",
+        "n" if saigon && !keeps_alveolar => "ŋ",
+        "n" => "n",
+        "ng" => "ŋ",
+        "nh" => {
+            if saigon {
+                "n"
+            } else {
+                "ɲ"
+            }
+        }
+        "p" => "p",
+        "t" if saigon && !keeps_alveolar => "k",
+        "t" => "t",
+        _ => return None,
+    })
+}
+
+fn tone_ipa(tone: Option<char>, stop_final: bool, dialect: Dialect) -> &'static str {
+    match (dialect, tone) {
+        (_, None) => "˧˧",
+        (_, Some('f')) => "˨˩",
+        (_, Some('s')) if stop_final => "˦˥",
+        (_, Some('s')) => "˧˥",
+        (_, Some('j')) if stop_final => "˨˩",
+        (Dialect::Hanoi, Some('r')) => "˧˩˧",
+        (Dialect::Hanoi, Some('x')) => "˧ˀ˥",
+        (Dialect::Hanoi, _) => "˨˩ˀ",
+        (Dialect::Saigon, Some('r' | 'x')) => "˨˩˦",
+        (Dialect::Saigon, _) => "˨˩˨",
+    }
+}
+
+fn parse_syllable(word: &[char]) -> Option<SyllableParts> {
+    if !word.iter().all(|ch| is_vietnamese_letter(*ch)) {
+        return None;
+    }
+    [TonePlacement::Orthographic, TonePlacement::NucleusOnly]
+        .iter()
+        .find_map(|placement| {
+            VitypeEngine::for_word(word, *placement)
+                .validate_syllable()
+                .ok()?
+        })
+}
+
+// ==================== Public Functions ====================
+
+/// Transcribes one written syllable, or returns `None` when it is not valid Vietnamese.
+pub(crate) fn transcribe_syllable(word: &[char], dialect: Dialect) -> Option<IpaSyllable> {
+    let parts = parse_syllable(word)?;
+    let mut onset = parts.onset_lower();
+    let nucleus = parts.nucleus_lower();
+    let coda = parts.coda_lower();
+
+    // The scan leaves the i of "gì"/"gìn" in the nucleus; it still spells the gi onset.
+    if onset == "g" && nucleus.starts_with('i') {
+        onset = "gi".to_string();
+    }
+    let (has_glide, core) = split_glide(&nucleus);
+    // "giếng" writes the iê diphthong with the onset's i.
+    let core = if onset == "gi" && core == "ê" && !coda.is_empty() {
+        "iê"
+    } else {
+        core
+    };
+
+    let glide = if has_glide || (onset == "qu" && dialect == Dialect::Hanoi) {
+        "w"
+    } else {
+        ""
+    };
+    Some(IpaSyllable {
+        onset: onset_ipa(&onset, dialect)?,
+        glide,
+        nucleus: nucleus_ipa(core, &coda, dialect)?,
+        coda: coda_ipa(&coda, core, dialect)?,
+        tone: tone_ipa(parts.tone, is_stop_coda(&coda), dialect),
+    })
+}
+
+/// Replaces every Vietnamese syllable in `text` with its IPA transcription; tokens
+/// that cannot be parsed and everything between tokens are copied unchanged.
+pub(crate) fn transcribe(text: &str, dialect: Dialect) -> String {
+    let mut output = String::with_capacity(text.len() * 2);
+    let mut cursor = 0;
+    for token in word_tokens(text) {
+        output.push_str(&text[cursor..token.byte_start]);
+        match transcribe_syllable(&token.chars, dialect) {
+            Some(syllable) => output.push_str(&syllable.to_ipa()),
+            None => output.push_str(&text[token.byte_start..token.byte_end]),
+        }
+        cursor = token.byte_end;
+    }
+    output.push_str(&text[cursor..]);
+    output
+}
@@ -0,0 +1,297 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use crate::common::lower_char;
+use crate::syllable::{is_vietnamese_letter, word_tokens};
+
+const BINARY_MAGIC: &[u8; 4] = b"VTLM";
+const BINARY_VERSION: u8 = 1;
+
+// ==================== Lexicon ====================
+
+/// Unigram and bigram counts over lowercase syllables. Bigrams are indexed by
+/// their first word so the words following one are found without a full scan.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Lexicon {
+    unigrams: HashMap<String, u32>,
+    bigrams: HashMap<String, HashMap<String, u32>>,
+    total: u64,
+}
+
+const BIGRAM_BACKOFF_WEIGHT: f64 = 2.0;
+const UNSEEN_COUNT: f64 = 0.1;
+
+impl Lexicon {
+    /// Parses the bundled text format: `syllable<TAB>count` for unigrams and
+    /// `syllable syllable<TAB>count` for bigrams. Lines starting with `#` are comments.
+    pub(crate) fn parse(text: &str) -> Lexicon {
+        let mut lexicon = Lexicon::default();
+        for line in text.lines() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, count)) = line.split_once('\t') else {
+                continue;
+            };
+            let Ok(count) = count.trim().parse::<u32>() else {
+                continue;
+            };
+            match key.split_once(' ') {
+                Some((first, second)) => lexicon.add_bigram(first, second, count),
+                None => lexicon.add_unigram(key, count),
+            }
+        }
+        lexicon
+    }
+
+    pub(crate) fn add_unigram(&mut self, word: &str, count: u32) {
+        let entry = self.unigrams.entry(word.to_string()).or_insert(0);
+        *entry = entry.saturating_add(count);
+        self.total = self.total.saturating_add(u64::from(count));
+    }
+
+    pub(crate) fn add_bigram(&mut self, first: &str, second: &str, count: u32) {
+        let entry = self
+            .bigrams
+            .entry(first.to_string())
+            .or_default()
+            .entry(second.to_string())
+            .or_insert(0);
+        *entry = entry.saturating_add(count);
+    }
+
+    pub(crate) fn unigram_count(&self, word: &str) -> u32 {
+        self.unigrams.get(word).copied().unwrap_or(0)
+    }
+
+    pub(crate) fn bigram_count(&self, first: &str, second: &str) -> u32 {
+        self.bigrams
+            .get(first)
+            .and_then(|followers| followers.get(second))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn contains(&self, word: &str) -> bool {
+        self.unigrams.contains_key(word)
+    }
+
+    pub(crate) fn unigrams(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.unigrams
+            .iter()
+            .map(|(word, count)| (word.as_str(), *count))
+    }
+
+    /// Words seen after `previous`, with their bigram counts.
+    pub(crate) fn followers<'a>(
+        &'a self,
+        previous: &'a str,
+    ) -> impl Iterator<Item = (&'a str, u32)> {
+        self.bigrams
+            .get(previous)
+            .into_iter()
+            .flatten()
+            .map(|(second, count)| (second.as_str(), *count))
+    }
+
+    /// Counts the syllables of a plain-text corpus. Tokens with non-Vietnamese
+    /// letters are skipped; bigrams are only counted across plain whitespace.
+    pub(crate) fn train(&mut self, text: &str) {
+        let mut previous: Option<(String, usize)> = None;
+        for token in word_tokens(text) {
+            if !token.chars.iter().all(|ch| is_vietnamese_letter(*ch)) {
+                previous = None;
+                continue;
+            }
+            let word: String = token.chars.iter().map(|ch| lower_char(*ch)).collect();
+            self.add_unigram(&word, 1);
+            if let Some((first, end)) = &previous {
+                if text[*end..token.byte_start]
+                    .chars()
+                    .all(char::is_whitespace)
+                {
+                    self.add_bigram(first, &word, 1);
+                }
+            }
+            previous = Some((word, token.byte_end));
+        }
+    }
+
+    pub(crate) fn train_from_file(&mut self, path: &Path) -> io::Result<()> {
+        self.train(&std::fs::read_to_string(path)?);
+        Ok(())
+    }
+
+    fn unigram_probability(&self, word: &str) -> f64 {
+        let vocabulary = self.unigrams.len() as f64 + 1.0;
+        let count = f64::from(self.unigram_count(word));
+        (count + UNSEEN_COUNT) / (self.total as f64 + UNSEEN_COUNT * vocabulary)
+    }
+
+    /// P(word | previous), interpolated with the unigram probability.
+    pub(crate) fn conditional_probability(&self, previous: Option<&str>, word: &str) -> f64 {
+        let unigram = self.unigram_probability(word);
+        let Some(previous) = previous else {
+            return unigram;
+        };
+        let pair = f64::from(self.bigram_count(previous, word));
+        let context = f64::from(self.unigram_count(previous));
+        (pair + BIGRAM_BACKOFF_WEIGHT * unigram) / (context + BIGRAM_BACKOFF_WEIGHT)
+    }
+
+    /// Log-probability of `word` given its neighbours: P(word | previous) · P(next | word).
+    pub(crate) fn context_score(
+        &self,
+        previous: Option<&str>,
+        word: &str,
+        next: Option<&str>,
+    ) -> f64 {
+        let mut score = self.conditional_probability(previous, word).ln();
+        if let Some(next) = next {
+            score += self.conditional_probability(Some(word), next).ln();
+        }
+        score
+    }
+}
+
+// ==================== Binary Format ====================
+//
+// "VTLM", version byte, then LEB128 varints:
+//   vocabulary size, then per word: byte length, UTF-8 bytes, count
+//   bigram count, then per bigram: first word index, second word index, count
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn read_varint(bytes: &[u8], offset: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*offset)?;
+        *offset += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+        shift += 7;
+        if shift >= 64 {
+            return None;
+        }
+    }
+}
+
+impl Lexicon {
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut words: Vec<(&String, &u32)> = self.unigrams.iter().collect();
+        words.sort();
+        let index_of: HashMap<&str, usize> = words
+            .iter()
+            .enumerate()
+            .map(|(index, (word, _))| (word.as_str(), index))
+            .collect();
+
+        let mut out = Vec::new();
+        out.extend_from_slice(BINARY_MAGIC);
+        out.push(BINARY_VERSION);
+        write_varint(&mut out, words.len() as u64);
+        for (word, count) in &words {
+            write_varint(&mut out, word.len() as u64);
+            out.extend_from_slice(word.as_bytes());
+            write_varint(&mut out, u64::from(**count));
+        }
+
+        let mut pairs: Vec<(usize, usize, u32)> = self
+            .bigrams
+            .iter()
+            .flat_map(|(first, followers)| {
+                followers
+                    .iter()
+                    .map(move |(second, count)| (first, second, count))
+            })
+            .filter_map(|(first, second, count)| {
+                Some((
+                    *index_of.get(first.as_str())?,
+                    *index_of.get(second.as_str())?,
+                    *count,
+                ))
+            })
+            .collect();
+        pairs.sort();
+        write_varint(&mut out, pairs.len() as u64);
+        for (first, second, count) in pairs {
+            write_varint(&mut out, first as u64);
+            write_varint(&mut out, second as u64);
+            write_varint(&mut out, u64::from(count));
+        }
+        out
+    }
+
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Option<Lexicon> {
+        if bytes.get(..4)? != BINARY_MAGIC || *bytes.get(4)? != BINARY_VERSION {
+            return None;
+        }
+        let mut offset = 5;
+        let mut lexicon = Lexicon::default();
+
+        let word_count = read_varint(bytes, &mut offset)? as usize;
+        let mut words: Vec<String> = Vec::with_capacity(word_count.min(1 << 16));
+        for _ in 0..word_count {
+            let length = read_varint(bytes, &mut offset)? as usize;
+            let end = offset.checked_add(length)?;
+            let word = std::str::from_utf8(bytes.get(offset..end)?).ok()?;
+            offset = end;
+            let count = u32::try_from(read_varint(bytes, &mut offset)?).ok()?;
+            lexicon.add_unigram(word, count);
+            words.push(word.to_string());
+        }
+
+        let pair_count = read_varint(bytes, &mut offset)? as usize;
+        for _ in 0..pair_count {
+            let first = words.get(read_varint(bytes, &mut offset)? as usize)?;
+            let second = words.get(read_varint(bytes, &mut offset)? as usize)?;
+            let count = u32::try_from(read_varint(bytes, &mut offset)?).ok()?;
+            lexicon.add_bigram(first, second, count);
+        }
+
+        if offset != bytes.len() {
+            return None;
+        }
+        Some(lexicon)
+    }
+
+    /// Loads a binary lexicon, or a count file in the bundled text format.
+    pub(crate) fn load(path: &Path) -> io::Result<Lexicon> {
+        let bytes = std::fs::read(path)?;
+        if bytes.starts_with(BINARY_MAGIC) {
+            return Lexicon::from_bytes(&bytes).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid ViType lexicon")
+            });
+        }
+        let text = String::from_utf8(bytes)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        Ok(Lexicon::parse(&text))
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> io::Result<()> {
+        std::fs::write(path, self.to_bytes())
+    }
+}
+
+/// The bundled lexicon is a placeholder: about 1,500 common syllables and
+/// compounds with counts estimated from their frequency rank, not measured on a
+/// corpus. Confusable checks, predictions and restoration use it unless a host
+/// loads or trains a real lexicon. It ships in the binary format, built from the
+/// text source `data/lexicon.txt`.
+pub(crate) static DEFAULT_LEXICON: Lazy<Lexicon> =
+    Lazy::new(|| Lexicon::from_bytes(include_bytes!("../data/lexicon.vtlm")).unwrap_or_default());
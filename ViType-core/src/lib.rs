@@ -1,21 +1,56 @@
+mod caret;
+mod collation;
 mod common;
+mod confusable;
 mod diacritics;
+mod edit_script;
 mod ffi;
+mod force_vietnamese;
+mod han_nom;
+mod inventory;
+mod ipa;
+mod lexicon;
+mod literal_context;
+mod loanword;
+mod normalize;
+mod orthography;
+mod predict;
+mod preedit;
+mod provenance;
+mod restore;
+mod session;
+mod smart_backspace;
+mod spellcheck;
+mod surrounding;
+mod syllable;
 mod telex;
+mod unconverted;
+mod undo;
 mod vni;
 
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
 
 pub use ffi::VitypeTransformResult;
 
 // Re-export crate-visible types from common (keep public API surface minimal)
 pub(crate) use common::{
-    InputMethod, KeyTransformAction, OutputEncoding, TonePlacement, WTransformKind,
+    IYStyle, InputMethod, KeyTransformAction, OutputEncoding, TonePlacement, WTransformKind,
 };
 
 // Use internal items from common
 use common::{is_vowel, lower_char, BASE_VOWELS, TONED_TO_BASE, VOWEL_TO_TONED};
 
+use diacritics::{compose_tone, split_vowel_and_tone};
+
+use han_nom::HanNomState;
+use literal_context::LiteralContextOptions;
+use loanword::LoanwordLexicon;
+use normalize::restyle_i_y;
+use orthography::OrthographyPack;
+use predict::PredictionState;
+use provenance::WordSnapshot;
+
 // Use internal items from telex and vni
 use telex::is_telex_word_boundary;
 use vni::is_vni_word_boundary;
@@ -28,6 +63,7 @@ const HISTORY_WORD_LIMIT: usize = 3;
 struct WordSegment {
     buffer: Vec<char>,
     raw_buffer: Vec<char>,
+    provenance: Vec<Vec<usize>>,
     is_foreign_mode: bool,
     transforms_locked: bool,
 }
@@ -41,7 +77,12 @@ enum HistorySegment {
 pub struct VitypeEngine {
     buffer: Vec<char>,
     raw_buffer: Vec<char>,
+    provenance: Vec<Vec<usize>>,
+    word_snapshots: Vec<WordSnapshot>,
     history: VecDeque<HistorySegment>,
+    following: VecDeque<HistorySegment>,
+    history_word_limit: usize,
+    caret: Option<usize>,
     is_foreign_mode: bool,
     transforms_locked: bool,
     last_transform_key: Option<char>,
@@ -49,7 +90,21 @@ pub struct VitypeEngine {
     suppressed_transform_key: Option<char>,
     auto_fix_tone: bool,
     free_tone_placement: bool,
+    mixed_case_is_foreign: bool,
+    smart_numeric_context: bool,
+    smart_backspace: bool,
+    vietnamese_forced: bool,
+    forced_vietnamese_words: HashSet<String>,
+    numeric_context_skipped: bool,
     tone_placement: TonePlacement,
+    i_y_style: IYStyle,
+    orthography_packs: Vec<OrthographyPack>,
+    loanwords: Option<Arc<LoanwordLexicon>>,
+    loanword_rewritten: bool,
+    literal_context: bool,
+    literal_context_options: LiteralContextOptions,
+    han_nom: HanNomState,
+    prediction: PredictionState,
     output_encoding: OutputEncoding,
     input_method: InputMethod,
 }
@@ -59,7 +114,12 @@ impl VitypeEngine {
         Self {
             buffer: Vec::new(),
             raw_buffer: Vec::new(),
+            provenance: Vec::new(),
+            word_snapshots: Vec::new(),
             history: VecDeque::new(),
+            following: VecDeque::new(),
+            history_word_limit: HISTORY_WORD_LIMIT,
+            caret: None,
             is_foreign_mode: false,
             transforms_locked: false,
             last_transform_key: None,
@@ -67,7 +127,21 @@ impl VitypeEngine {
             suppressed_transform_key: None,
             auto_fix_tone: true,
             free_tone_placement: false,
+            mixed_case_is_foreign: false,
+            smart_numeric_context: false,
+            smart_backspace: false,
+            vietnamese_forced: false,
+            forced_vietnamese_words: HashSet::new(),
+            numeric_context_skipped: false,
             tone_placement: TonePlacement::Orthographic,
+            i_y_style: IYStyle::Unchanged,
+            orthography_packs: Vec::new(),
+            loanwords: None,
+            loanword_rewritten: false,
+            literal_context: false,
+            literal_context_options: LiteralContextOptions::default(),
+            han_nom: HanNomState::default(),
+            prediction: PredictionState::default(),
             output_encoding: OutputEncoding::Unicode,
             input_method: InputMethod::Telex,
         }
@@ -108,6 +182,10 @@ impl VitypeEngine {
         self.free_tone_placement = enabled;
     }
 
+    pub(crate) fn set_mixed_case_is_foreign(&mut self, enabled: bool) {
+        self.mixed_case_is_foreign = enabled;
+    }
+
     pub(crate) fn set_input_method(&mut self, method: InputMethod) {
         self.input_method = method;
     }
@@ -120,30 +198,75 @@ impl VitypeEngine {
         self.tone_placement = placement;
     }
 
+    pub(crate) fn set_i_y_style(&mut self, style: IYStyle) {
+        self.i_y_style = style;
+    }
+
     pub(crate) fn output_encoding(&self) -> OutputEncoding {
         self.output_encoding
     }
 
     pub(crate) fn process(&mut self, input: &str) -> Option<KeyTransformAction> {
+        let old_buffer = self.buffer.clone();
+        let old_raw = self.raw_buffer.clone();
+        let action = self.process_key(input);
+        self.record_keystroke(&old_buffer, &old_raw);
+        self.repair_provenance_if_needed();
+        action
+    }
+
+    fn process_key(&mut self, input: &str) -> Option<KeyTransformAction> {
         let mut chars = input.chars();
         let ch = chars.next()?;
         if chars.next().is_some() {
             return None;
         }
+        // Without the caret-aware methods the host can only type at the end.
+        if self.is_caret_inside_word() {
+            self.reset();
+        }
+        self.dismiss_han_nom_candidates();
+        self.clear_predictions();
 
         if is_word_boundary(ch, self.input_method) {
+            let action = match self.try_literal_context_boundary(ch) {
+                Some(action) => action,
+                None => match self.revert_remembered_prefix_before_commit(ch) {
+                    Some(action) => Some(action),
+                    None => self.apply_i_y_style_before_commit(ch),
+                },
+            };
             if should_clear_history_on_boundary(ch) {
                 self.reset_current_word();
                 self.history.clear();
-                return None;
+                return action;
             }
 
             self.commit_current_word_to_history_if_needed();
             self.push_boundary_to_history(ch);
             self.reset_current_word();
+            self.open_han_nom_after_commit();
+            return action;
+        }
+
+        if self.literal_context {
+            self.is_foreign_mode = true;
+            self.raw_buffer.push(ch);
+            self.buffer.push(ch);
+            return None;
+        }
+
+        if self.is_vni_numeric_context(ch) {
+            self.clear_transform_state();
+            self.raw_buffer.push(ch);
+            self.buffer.push(ch);
             return None;
         }
 
+        if let Some(result) = self.try_loanword_policy(ch) {
+            return result;
+        }
+
         let previous_buffer_count = self.buffer.len();
         let ch_lower = lower_char(ch);
         if let Some(suppressed) = self.suppressed_transform_key {
@@ -159,6 +282,11 @@ impl VitypeEngine {
             return None;
         }
 
+        if let Some(action) = self.try_pack_letter_escape(ch) {
+            self.raw_buffer.pop();
+            return self.return_action_or_fallback(action, previous_buffer_count);
+        }
+
         if let Some(action) = self.try_escape_sequence(ch) {
             self.raw_buffer.pop();
             return self.return_action_or_fallback(action, previous_buffer_count);
@@ -176,6 +304,10 @@ impl VitypeEngine {
             return self.handle_invalid_syllable_if_needed(previous_buffer_count);
         }
 
+        if let Some(action) = self.try_pack_letter_transform(ch) {
+            return self.return_action_or_fallback(action, previous_buffer_count);
+        }
+
         if let Some(action) = self.try_consonant_transform(ch) {
             return self.return_action_or_fallback(action, previous_buffer_count);
         }
@@ -184,6 +316,12 @@ impl VitypeEngine {
             return self.return_action_or_fallback(action, previous_buffer_count);
         }
 
+        if self.rejects_tone_on_pack_letter(ch) {
+            self.clear_transform_state();
+            self.transforms_locked = true;
+            return None;
+        }
+
         if let Some(action) = self.try_tone_mark(ch) {
             return self.return_action_or_fallback(action, previous_buffer_count);
         }
@@ -208,6 +346,37 @@ impl VitypeEngine {
         self.handle_invalid_syllable_if_needed(previous_buffer_count)
     }
 
+    /// Rewrites the finished word to the configured i/y style; the action also
+    /// carries the boundary character that triggered the commit.
+    fn apply_i_y_style_before_commit(&mut self, boundary: char) -> Option<KeyTransformAction> {
+        if self.is_foreign_mode || self.transforms_locked {
+            return None;
+        }
+        let restyled = restyle_i_y(&self.buffer, self.i_y_style)?;
+        let start = self
+            .buffer
+            .iter()
+            .zip(restyled.iter())
+            .position(|(old, new)| old != new)?;
+        self.buffer = restyled;
+        // The letter's own keystroke changes too, so the raw word still replays to
+        // what is on screen; the snapshots describe the old spelling.
+        let letter_key = self.provenance.get(start).and_then(|keys| keys.first());
+        if let Some(raw) = letter_key.and_then(|key| self.raw_buffer.get_mut(*key)) {
+            if matches!(lower_char(*raw), 'i' | 'y') {
+                let (base, _) = split_vowel_and_tone(self.buffer[start]);
+                *raw = base;
+            }
+        }
+        self.word_snapshots.clear();
+        let mut output_text = self.buffer_string_from(start);
+        output_text.push(boundary);
+        Some(KeyTransformAction {
+            delete_count: self.buffer.len() - start,
+            text: output_text,
+        })
+    }
+
     // ==================== Dispatch Methods ====================
 
     fn try_escape_sequence(&mut self, ch: char) -> Option<KeyTransformAction> {
@@ -948,11 +1117,19 @@ impl VitypeEngine {
         })
     }
 
+    fn is_foreign_spelling(&self) -> bool {
+        self.is_invalid_vietnamese_syllable(self.buffer.len())
+            || (self.mixed_case_is_foreign && has_internal_capital(&self.raw_buffer))
+    }
+
     fn handle_invalid_syllable_if_needed(
         &mut self,
         previous_buffer_count: usize,
     ) -> Option<KeyTransformAction> {
-        if !self.is_invalid_vietnamese_syllable(self.buffer.len()) {
+        if !self.is_foreign_spelling()
+            || self.loanword_keeps_vietnamese()
+            || self.is_vietnamese_forced()
+        {
             return None;
         }
 
@@ -993,7 +1170,8 @@ impl VitypeEngine {
     }
 
     fn is_invalid_vietnamese_syllable(&self, before: usize) -> bool {
-        self.has_multiple_vowel_clusters(before) || self.has_invalid_final_consonant(before)
+        (self.has_multiple_vowel_clusters(before) && !self.allows_multiple_vowel_clusters(before))
+            || (self.has_invalid_final_consonant(before) && !self.has_pack_final_consonant(before))
     }
 
     fn has_invalid_final_consonant(&self, before: usize) -> bool {
@@ -1008,7 +1186,7 @@ impl VitypeEngine {
 
         let mut tail = String::new();
         for ch in &self.buffer[last_vowel_index + 1..before] {
-            if is_vowel(*ch) {
+            if self.is_vowel_with_packs(*ch) {
                 return false;
             }
             if !ch.is_alphabetic() {
@@ -1085,6 +1263,7 @@ impl VitypeEngine {
         })
     }
 
+    #[allow(clippy::match_like_matches_macro)]
     fn is_valid_vowel_cluster_chars(cluster: &[char]) -> bool {
         match cluster {
             ['a', 'i']
@@ -1146,6 +1325,7 @@ impl VitypeEngine {
         let mut cluster: Vec<char> = Vec::with_capacity(vowel_indices.len());
         for index in vowel_indices {
             let base_vowel = self.get_base_vowel(self.buffer[index]);
+            let base_vowel = self.pack_vowel_base(base_vowel).unwrap_or(base_vowel);
             let normalized = match Self::normalize_vowel_for_cluster(base_vowel) {
                 Some(ch) => ch,
                 None => return false,
@@ -1164,7 +1344,7 @@ impl VitypeEngine {
         let mut pending_gi_i: Option<usize> = None;
 
         for index in 0..limit {
-            if !is_vowel(self.buffer[index]) {
+            if !self.is_vowel_with_packs(self.buffer[index]) {
                 continue;
             }
 
@@ -1192,7 +1372,10 @@ impl VitypeEngine {
     }
 
     fn find_target_vowel_index(&self, before: usize) -> Option<usize> {
-        if !self.free_tone_placement && !self.is_valid_tone_cluster(before) {
+        if !self.free_tone_placement
+            && !self.is_vietnamese_forced()
+            && !self.is_valid_tone_cluster(before)
+        {
             return None;
         }
 
@@ -1270,7 +1453,7 @@ impl VitypeEngine {
         let mut index = self.buffer.len();
         while index > 0 {
             index -= 1;
-            if TONED_TO_BASE.contains_key(&self.buffer[index]) {
+            if self.split_tone_with_packs(self.buffer[index]).1.is_some() {
                 return Some(index);
             }
         }
@@ -1290,6 +1473,13 @@ impl VitypeEngine {
         let vowel_index = self.find_target_vowel_index(trigger_index)?;
         let vowel = self.buffer[vowel_index];
 
+        let base_vowel = self.get_base_vowel(vowel);
+        // Resolve the toned form first so a vowel without one changes nothing.
+        let toned_vowel = match tone_key {
+            'z' => base_vowel,
+            _ => compose_tone(base_vowel, Some(tone_key))?,
+        };
+
         let mut start_index = vowel_index;
         if let Some(earliest) = self.clear_other_tones(vowel_index, trigger_index) {
             if earliest < start_index {
@@ -1298,7 +1488,6 @@ impl VitypeEngine {
         }
 
         if tone_key == 'z' {
-            let base_vowel = self.get_base_vowel(vowel);
             let mut changed = false;
             if base_vowel != vowel {
                 self.buffer[vowel_index] = base_vowel;
@@ -1322,11 +1511,7 @@ impl VitypeEngine {
             });
         }
 
-        let base_vowel = self.get_base_vowel(vowel);
-        let tone_map = VOWEL_TO_TONED.get(&base_vowel)?;
-        let toned_vowel = tone_map.get(&tone_key)?;
-
-        self.buffer[vowel_index] = *toned_vowel;
+        self.buffer[vowel_index] = toned_vowel;
         self.buffer.pop();
         self.last_transform_key = Some(store_last_key);
         self.last_w_transform_kind = WTransformKind::None;
@@ -1505,13 +1690,13 @@ impl VitypeEngine {
         suppressed_key: char,
     ) -> Option<KeyTransformAction> {
         let toned_index = self.find_last_toned_vowel_index()?;
-        let (base_vowel, last_tone_key) = TONED_TO_BASE.get(&self.buffer[toned_index])?;
-        if lower_char(*last_tone_key) != internal_tone_key {
+        let (base_vowel, last_tone_key) = self.split_tone_with_packs(self.buffer[toned_index]);
+        if last_tone_key.map(lower_char) != Some(internal_tone_key) {
             return None;
         }
 
         let delete_count = self.buffer.len() - toned_index;
-        self.buffer[toned_index] = *base_vowel;
+        self.buffer[toned_index] = base_vowel;
         self.buffer.push(key_to_push);
         self.clear_last_transform_and_suppress(suppressed_key);
         let output_text = self.buffer_string_from(toned_index);
@@ -1521,6 +1706,7 @@ impl VitypeEngine {
         })
     }
 
+    #[allow(clippy::unnecessary_map_or)]
     fn clear_other_tones(&mut self, except_index: usize, before: usize) -> Option<usize> {
         let mut earliest: Option<usize> = None;
         let limit = before.min(self.buffer.len());
@@ -1529,9 +1715,9 @@ impl VitypeEngine {
                 continue;
             }
             let ch = self.buffer[idx];
-            if let Some((base, _)) = TONED_TO_BASE.get(&ch) {
-                if self.buffer[idx] != *base {
-                    self.buffer[idx] = *base;
+            if let (base, Some(_)) = self.split_tone_with_packs(ch) {
+                if self.buffer[idx] != base {
+                    self.buffer[idx] = base;
                     if earliest.map_or(true, |current| idx < current) {
                         earliest = Some(idx);
                     }
@@ -1609,10 +1795,7 @@ impl VitypeEngine {
         if BASE_VOWELS.contains(&ch) {
             return ch;
         }
-        if let Some((base, _)) = TONED_TO_BASE.get(&ch) {
-            return *base;
-        }
-        ch
+        self.split_tone_with_packs(ch).0
     }
 
     fn buffer_string_from(&self, start: usize) -> String {
@@ -1622,14 +1805,24 @@ impl VitypeEngine {
     fn reset_current_word(&mut self) {
         self.buffer.clear();
         self.raw_buffer.clear();
+        self.provenance.clear();
+        self.word_snapshots.clear();
         self.clear_transform_state();
         self.is_foreign_mode = false;
         self.transforms_locked = false;
+        self.loanword_rewritten = false;
+        self.numeric_context_skipped = false;
+        self.vietnamese_forced = false;
+        self.caret = None;
     }
 
     pub(crate) fn reset(&mut self) {
+        self.dismiss_han_nom_candidates();
+        self.clear_predictions();
         self.reset_current_word();
         self.history.clear();
+        self.following.clear();
+        self.literal_context = false;
     }
 
     fn commit_current_word_to_history_if_needed(&mut self) {
@@ -1644,11 +1837,14 @@ impl VitypeEngine {
 
         let buffer = std::mem::take(&mut self.buffer);
         let raw_buffer = std::mem::take(&mut self.raw_buffer);
+        let provenance = std::mem::take(&mut self.provenance);
         let is_foreign_mode = self.is_foreign_mode;
         let transforms_locked = self.transforms_locked;
+        self.word_snapshots.clear();
         self.history.push_back(HistorySegment::Word(WordSegment {
             buffer,
             raw_buffer,
+            provenance,
             is_foreign_mode,
             transforms_locked,
         }));
@@ -1673,7 +1869,7 @@ impl VitypeEngine {
             .filter(|seg| matches!(seg, HistorySegment::Word(_)))
             .count();
 
-        while word_count > HISTORY_WORD_LIMIT {
+        while word_count > self.history_word_limit {
             match self.history.pop_front() {
                 Some(HistorySegment::Word(_)) => word_count -= 1,
                 Some(HistorySegment::Boundary(_)) => {}
@@ -1692,6 +1888,8 @@ impl VitypeEngine {
             Some(HistorySegment::Word(word)) => {
                 self.buffer = word.buffer;
                 self.raw_buffer = word.raw_buffer;
+                self.provenance = word.provenance;
+                self.word_snapshots.clear();
                 self.is_foreign_mode = word.is_foreign_mode;
                 self.transforms_locked = word.transforms_locked;
                 self.clear_transform_state();
@@ -1705,12 +1903,15 @@ impl VitypeEngine {
         }
     }
 
+    /// Replays `raw` as the current word in a fresh engine with every setting and
+    /// the per-word overrides of this one.
     fn rebuild_current_word_from_raw(&self, raw: &[char]) -> VitypeEngine {
         let mut engine = VitypeEngine::new();
-        engine.auto_fix_tone = self.auto_fix_tone;
-        engine.tone_placement = self.tone_placement;
-        engine.output_encoding = self.output_encoding;
-        engine.input_method = self.input_method;
+        engine.copy_configuration_from(self);
+        engine.literal_context = self.literal_context;
+        engine.numeric_context_skipped = self.numeric_context_skipped;
+        engine.vietnamese_forced = self.vietnamese_forced;
+        engine.forced_vietnamese_words = self.forced_vietnamese_words.clone();
 
         for ch in raw {
             let input = ch.to_string();
@@ -1723,6 +1924,8 @@ impl VitypeEngine {
     fn adopt_current_word_state_from(&mut self, other: VitypeEngine) {
         self.buffer = other.buffer;
         self.raw_buffer = other.raw_buffer;
+        self.provenance = other.provenance;
+        self.word_snapshots = other.word_snapshots;
         self.is_foreign_mode = other.is_foreign_mode;
         self.transforms_locked = other.transforms_locked;
         self.last_transform_key = other.last_transform_key;
@@ -1730,45 +1933,24 @@ impl VitypeEngine {
         self.suppressed_transform_key = other.suppressed_transform_key;
     }
 
-    fn delete_last_character_in_current_word(&mut self) {
-        if self.buffer.is_empty() {
-            return;
-        }
-
-        let mut desired_buffer = self.buffer.clone();
-        desired_buffer.pop();
-
-        if desired_buffer.is_empty() {
-            self.reset_current_word();
-            return;
-        }
-
-        let raw_len = self.raw_buffer.len();
-        let max_backtrack = raw_len.min(6);
-        for remove_count in 1..=max_backtrack {
-            let candidate_len = raw_len.saturating_sub(remove_count);
-            let candidate_raw = &self.raw_buffer[..candidate_len];
-            let rebuilt = self.rebuild_current_word_from_raw(candidate_raw);
-            if rebuilt.buffer == desired_buffer {
-                self.adopt_current_word_state_from(rebuilt);
-                return;
-            }
-        }
-
-        // Fallback: best-effort single raw deletion.
-        self.buffer = desired_buffer;
-        self.raw_buffer.pop();
-        self.clear_transform_state();
-        self.is_foreign_mode = self.is_invalid_vietnamese_syllable(self.buffer.len());
-        if self.buffer.is_empty() {
-            self.transforms_locked = false;
+    /// Returns an action only when smart backspace removed a mark instead of a
+    /// char; otherwise the host deletes one char itself.
+    pub(crate) fn delete_last_character(&mut self) -> Option<KeyTransformAction> {
+        if self.is_caret_inside_word() {
+            self.reset();
+            return None;
         }
+        self.dismiss_han_nom_candidates();
+        self.clear_predictions();
+        let action = self.delete_last_character_before_caret();
+        self.refresh_literal_context();
+        self.repair_provenance_if_needed();
+        action
     }
 
-    pub(crate) fn delete_last_character(&mut self) {
+    fn delete_last_character_before_caret(&mut self) -> Option<KeyTransformAction> {
         if !self.buffer.is_empty() {
-            self.delete_last_character_in_current_word();
-            return;
+            return self.delete_last_mark_or_character_in_current_word();
         }
 
         // If we're not currently composing a word, we may be right after boundary characters.
@@ -1788,16 +1970,28 @@ impl VitypeEngine {
                 {
                     self.restore_last_word_from_history();
                 }
+                None
             }
             Some(HistorySegment::Word(_)) => {
                 // Cursor is at the end of a previously committed word (no trailing boundary).
                 if self.restore_last_word_from_history() {
-                    self.delete_last_character_in_current_word();
+                    return self.delete_last_mark_or_character_in_current_word();
                 }
+                None
             }
-            None => {}
+            None => None,
         }
     }
+
+    fn delete_last_mark_or_character_in_current_word(&mut self) -> Option<KeyTransformAction> {
+        if self.smart_backspace {
+            if let Some(action) = self.remove_last_mark() {
+                return Some(action);
+            }
+        }
+        self.delete_last_character_in_current_word();
+        None
+    }
 }
 
 // ==================== Helper Functions ====================
@@ -1809,6 +2003,13 @@ fn is_word_boundary(ch: char, input_method: InputMethod) -> bool {
     }
 }
 
+/// An uppercase letter right after a lowercase one ("iPhone", "macOS", "getUser").
+/// All-caps and title-case words never match.
+fn has_internal_capital(raw: &[char]) -> bool {
+    raw.windows(2)
+        .any(|pair| pair[0].is_lowercase() && pair[1].is_uppercase())
+}
+
 fn should_clear_history_on_boundary(ch: char) -> bool {
     ch == '\n' || ch == '\r'
 }
@@ -0,0 +1,191 @@
+use crate::common::KeyTransformAction;
+use crate::provenance::identity_provenance;
+use crate::{HistorySegment, VitypeEngine};
+
+// ==================== Options ====================
+
+/// Which kinds of tokens keep the rest of the token literal. A token runs from the
+/// last whitespace to the caret and may span several history segments.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct LiteralContextOptions {
+    /// `scheme://…`
+    pub(crate) urls: bool,
+    /// `name@host`
+    pub(crate) emails: bool,
+    /// `/usr/local`, `~/src`, `./build`, `C:\Windows`
+    pub(crate) paths: bool,
+    /// `snake_case`, `Type::method`, `camelCase.field`
+    pub(crate) identifiers: bool,
+}
+
+impl Default for LiteralContextOptions {
+    fn default() -> Self {
+        LiteralContextOptions {
+            urls: true,
+            emails: true,
+            paths: true,
+            identifiers: true,
+        }
+    }
+}
+
+impl LiteralContextOptions {
+    fn is_literal_token(&self, token: &[char]) -> bool {
+        let text: String = token.iter().collect::<String>().to_lowercase();
+        (self.urls && text.contains("://"))
+            || (self.emails && is_email_like(token))
+            || (self.paths && is_path_like(&text))
+            || (self.identifiers && is_identifier_like(token))
+    }
+}
+
+// ==================== Helper Functions ====================
+
+fn is_email_like(token: &[char]) -> bool {
+    token
+        .iter()
+        .position(|ch| *ch == '@')
+        .is_some_and(|at| at > 0 && token[at - 1].is_alphanumeric())
+}
+
+fn is_path_like(text: &str) -> bool {
+    ["/", "~/", "./", "../"]
+        .iter()
+        .any(|prefix| text.starts_with(prefix))
+        || text.contains('\\')
+}
+
+/// `_` or `::` anywhere, or a camelCase word followed by punctuation.
+fn is_identifier_like(token: &[char]) -> bool {
+    if token.contains(&'_') || token.windows(2).any(|pair| pair == [':', ':']) {
+        return true;
+    }
+    let Some(last) = token.last() else {
+        return false;
+    };
+    if last.is_alphanumeric() {
+        return false;
+    }
+    let word_start = token[..token.len() - 1]
+        .iter()
+        .rposition(|ch| !ch.is_alphanumeric())
+        .map_or(0, |index| index + 1);
+    token[word_start..token.len() - 1]
+        .windows(2)
+        .any(|pair| pair[0].is_lowercase() && pair[1].is_uppercase())
+}
+
+// ==================== Literal Context Methods on VitypeEngine ====================
+
+impl VitypeEngine {
+    pub(crate) fn set_literal_context_options(&mut self, options: LiteralContextOptions) {
+        self.literal_context_options = options;
+        self.refresh_literal_context();
+    }
+
+    /// Re-derives the context after a deletion, which may remove the character
+    /// that started it or step back into an earlier token.
+    pub(super) fn refresh_literal_context(&mut self) {
+        self.literal_context = self.is_literal_token(&self.current_token());
+    }
+
+    fn is_literal_token(&self, token: &[char]) -> bool {
+        self.literal_context_options.is_literal_token(token)
+    }
+
+    /// Raw keystrokes from the last whitespace to the caret, as far back as
+    /// history reaches.
+    fn current_token(&self) -> Vec<char> {
+        let mut text: Vec<char> = Vec::new();
+        for segment in &self.history {
+            match segment {
+                HistorySegment::Word(word) => text.extend(&word.raw_buffer),
+                HistorySegment::Boundary(chars) => text.extend(chars),
+            }
+        }
+        text.extend(&self.raw_buffer);
+        match text.iter().rposition(|ch| ch.is_whitespace()) {
+            Some(index) => text.split_off(index + 1),
+            None => text,
+        }
+    }
+
+    /// Called for a boundary key before the current word is committed. Returns
+    /// `Some(result)` while the token is literal; entering the context rewrites the
+    /// token's words back to their keystrokes, followed by `boundary`.
+    pub(super) fn try_literal_context_boundary(
+        &mut self,
+        boundary: char,
+    ) -> Option<Option<KeyTransformAction>> {
+        if boundary.is_whitespace() {
+            self.literal_context = false;
+            return None;
+        }
+        if self.literal_context {
+            return Some(None);
+        }
+        let mut token = self.current_token();
+        token.push(boundary);
+        if !self.is_literal_token(&token) {
+            return None;
+        }
+        self.literal_context = true;
+        Some(self.restore_token_to_raw(boundary))
+    }
+
+    fn restore_token_to_raw(&mut self, boundary: char) -> Option<KeyTransformAction> {
+        // History segments that belong to the token: everything after the last
+        // boundary containing whitespace.
+        let token_start = self
+            .history
+            .iter()
+            .rposition(|segment| match segment {
+                HistorySegment::Boundary(chars) => chars.iter().any(|ch| ch.is_whitespace()),
+                HistorySegment::Word(_) => false,
+            })
+            .map_or(0, |index| index + 1);
+
+        let mut delete_count = 0;
+        let mut text = String::new();
+        let mut changed = false;
+        for segment in self.history.iter_mut().skip(token_start) {
+            let (visible, raw) = match segment {
+                HistorySegment::Word(word) => {
+                    let visible = word.buffer.len();
+                    if word.buffer != word.raw_buffer {
+                        word.buffer = word.raw_buffer.clone();
+                        word.provenance = identity_provenance(word.buffer.len());
+                        word.is_foreign_mode = true;
+                        changed = true;
+                    }
+                    (visible, word.buffer.iter().collect::<String>())
+                }
+                HistorySegment::Boundary(chars) => (chars.len(), chars.iter().collect()),
+            };
+            if changed {
+                delete_count += visible;
+                text.push_str(&raw);
+            } else {
+                // Nothing before the first restored word needs rewriting.
+                delete_count = 0;
+                text.clear();
+            }
+        }
+
+        let visible = self.buffer.len();
+        if self.buffer != self.raw_buffer {
+            self.buffer = self.raw_buffer.clone();
+            self.reset_provenance();
+            changed = true;
+        }
+        self.is_foreign_mode = true;
+        self.clear_transform_state();
+        if !changed {
+            return None;
+        }
+        delete_count += visible;
+        text.extend(self.buffer.iter());
+        text.push(boundary);
+        Some(KeyTransformAction { delete_count, text })
+    }
+}
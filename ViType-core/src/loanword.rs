@@ -0,0 +1,163 @@
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+
+use crate::common::{lower_string, KeyTransformAction};
+use crate::VitypeEngine;
+
+/// Prefixes shorter than this never switch a word to literal output, so single
+/// letters such as "w" keep their Telex meaning.
+const DEFAULT_MIN_PREFIX_LENGTH: usize = 2;
+
+// ==================== Loanword Lexicon ====================
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum TokenPolicy {
+    /// Output the keystrokes unchanged while they spell a prefix of the token.
+    Literal,
+    /// Keep applying Vietnamese rules and never fall back to foreign mode.
+    Vietnamese,
+    /// Behave like `Vietnamese`, then write the given text once the token is complete.
+    Override(String),
+}
+
+/// Tokens matched case-insensitively against `raw_buffer` as it is typed.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LoanwordLexicon {
+    entries: Vec<(String, TokenPolicy)>,
+    pub(crate) min_prefix_length: usize,
+}
+
+impl Default for LoanwordLexicon {
+    fn default() -> Self {
+        LoanwordLexicon {
+            entries: Vec::new(),
+            min_prefix_length: DEFAULT_MIN_PREFIX_LENGTH,
+        }
+    }
+}
+
+impl LoanwordLexicon {
+    /// Parses `token<TAB>literal|vietnamese|override[<TAB>output]` lines; `#` starts
+    /// a comment. Returns `None` on an unknown policy or a missing override.
+    pub(crate) fn parse(text: &str) -> Option<LoanwordLexicon> {
+        let mut lexicon = LoanwordLexicon::default();
+        for line in text.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split('\t').map(str::trim);
+            let token = fields.next()?;
+            let policy = match fields.next()? {
+                "literal" => TokenPolicy::Literal,
+                "vietnamese" => TokenPolicy::Vietnamese,
+                "override" => TokenPolicy::Override(fields.next()?.to_string()),
+                _ => return None,
+            };
+            lexicon.insert(token, policy);
+        }
+        Some(lexicon)
+    }
+
+    pub(crate) fn insert(&mut self, token: &str, policy: TokenPolicy) {
+        let token = token.to_lowercase();
+        self.entries.retain(|(existing, _)| *existing != token);
+        self.entries.push((token, policy));
+    }
+
+    fn is_literal_prefix(&self, raw: &str) -> bool {
+        self.entries.iter().any(|(token, policy)| {
+            *policy == TokenPolicy::Literal
+                && token.starts_with(raw)
+                && raw.chars().count() >= self.min_prefix_length.min(token.chars().count())
+        })
+    }
+
+    fn override_for(&self, raw: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find_map(|(token, policy)| match policy {
+                TokenPolicy::Override(output) if token == raw => Some(output.as_str()),
+                _ => None,
+            })
+    }
+
+    fn keeps_vietnamese(&self, raw: &str) -> bool {
+        self.entries.iter().any(|(token, policy)| {
+            !matches!(policy, TokenPolicy::Literal) && token.starts_with(raw)
+        })
+    }
+}
+
+pub(crate) static BUNDLED_LOANWORDS: Lazy<LoanwordLexicon> =
+    Lazy::new(|| LoanwordLexicon::parse(include_str!("../data/loanwords.txt")).unwrap_or_default());
+
+// ==================== Helper Functions ====================
+
+/// The edit that turns `before` into `after`, or `None` when the host's default
+/// of inserting `key` already gives `after`.
+fn rewrite_action(before: &[char], after: &[char], key: char) -> Option<KeyTransformAction> {
+    if after.len() == before.len() + 1 && after.starts_with(before) && after.last() == Some(&key) {
+        return None;
+    }
+    let common = before
+        .iter()
+        .zip(after.iter())
+        .take_while(|(old, new)| old == new)
+        .count();
+    Some(KeyTransformAction {
+        delete_count: before.len() - common,
+        text: after[common..].iter().collect(),
+    })
+}
+
+// ==================== Loanword Methods on VitypeEngine ====================
+
+impl VitypeEngine {
+    pub(crate) fn set_loanword_lexicon(&mut self, lexicon: LoanwordLexicon) {
+        self.loanwords = Some(Arc::new(lexicon));
+    }
+
+    pub(crate) fn clear_loanword_lexicon(&mut self) {
+        self.loanwords = None;
+    }
+
+    /// Applies a literal or override policy for `ch` before the normal pipeline.
+    /// Returns `Some(result)` when the key was fully handled here.
+    pub(super) fn try_loanword_policy(&mut self, ch: char) -> Option<Option<KeyTransformAction>> {
+        let lexicon = self.loanwords.clone()?;
+        let mut raw = self.raw_buffer.clone();
+        raw.push(ch);
+        let raw_lower = lower_string(&raw);
+        let before = self.buffer.clone();
+
+        let buffer: Vec<char> = if lexicon.is_literal_prefix(&raw_lower) {
+            raw.clone()
+        } else if let Some(output) = lexicon.override_for(&raw_lower) {
+            output.chars().collect()
+        } else if self.loanword_rewritten {
+            // The word has left the token: replay it as if the lexicon were absent.
+            let loanwords = self.loanwords.take();
+            let rebuilt = self.rebuild_current_word_from_raw(&raw);
+            self.loanwords = loanwords;
+            self.adopt_current_word_state_from(rebuilt);
+            self.loanword_rewritten = false;
+            return Some(rewrite_action(&before, &self.buffer, ch));
+        } else {
+            return None;
+        };
+
+        self.raw_buffer = raw;
+        self.buffer = buffer;
+        self.clear_transform_state();
+        self.loanword_rewritten = true;
+        Some(rewrite_action(&before, &self.buffer, ch))
+    }
+
+    /// Whether the raw keystrokes are still a prefix of a token that must stay
+    /// Vietnamese, which suppresses the foreign-mode fallback.
+    pub(super) fn loanword_keeps_vietnamese(&self) -> bool {
+        self.loanwords
+            .as_ref()
+            .is_some_and(|lexicon| lexicon.keeps_vietnamese(&lower_string(&self.raw_buffer)))
+    }
+}
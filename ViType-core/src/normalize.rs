@@ -0,0 +1,102 @@
+use crate::common::{lower_char, IYStyle, TonePlacement};
+use crate::diacritics::{apply_tone, split_vowel_and_tone};
+use crate::syllable::{is_vietnamese_letter, word_tokens};
+use crate::VitypeEngine;
+
+// ==================== Helper Functions ====================
+
+/// Rebuilds `text`, passing every word token through `rewrite` and copying
+/// everything between tokens unchanged.
+fn rewrite_words(text: &str, mut rewrite: impl FnMut(&[char]) -> Option<Vec<char>>) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for token in word_tokens(text) {
+        output.push_str(&text[cursor..token.byte_start]);
+        match rewrite(&token.chars) {
+            Some(rewritten) => output.extend(rewritten),
+            None => output.push_str(&text[token.byte_start..token.byte_end]),
+        }
+        cursor = token.byte_end;
+    }
+    output.push_str(&text[cursor..]);
+    output
+}
+
+fn retone_word(word: &[char], tone_placement: TonePlacement) -> Option<Vec<char>> {
+    if !word.iter().all(|ch| is_vietnamese_letter(*ch)) {
+        return None;
+    }
+    let parts = VitypeEngine::for_word(word, tone_placement).split_syllable()?;
+    if parts.tone_marks != 1 {
+        return None;
+    }
+    let toned_index = parts.toned_index?;
+    let tone = parts.tone?;
+
+    let mut untoned = word.to_vec();
+    untoned[toned_index] = split_vowel_and_tone(word[toned_index]).0;
+    let target = VitypeEngine::for_word(&untoned, tone_placement).tone_target_index()?;
+    if target == toned_index {
+        return None;
+    }
+
+    let mut moved = untoned;
+    moved[target] = apply_tone(moved[target], Some(tone))?;
+    match VitypeEngine::for_word(&moved, tone_placement).validate_syllable() {
+        Ok(Some(_)) => Some(moved),
+        _ => None,
+    }
+}
+
+/// Swaps a lone final i/y after h, k, l, m, s or t to the requested style, keeping
+/// tone and case. "uy", "ay", "qu" and "gi" are left alone because there the two
+/// letters are not interchangeable.
+pub(crate) fn restyle_i_y(word: &[char], style: IYStyle) -> Option<Vec<char>> {
+    let replacement = match style {
+        IYStyle::Unchanged => return None,
+        IYStyle::PreferI => 'i',
+        IYStyle::PreferY => 'y',
+    };
+    if !word.iter().all(|ch| is_vietnamese_letter(*ch)) {
+        return None;
+    }
+    let parts = VitypeEngine::for_word(word, TonePlacement::Orthographic).split_syllable()?;
+    if !parts.coda.is_empty()
+        || parts.nucleus.len() != 1
+        || !matches!(
+            parts.onset_lower().as_str(),
+            "h" | "k" | "l" | "m" | "s" | "t"
+        )
+    {
+        return None;
+    }
+
+    let index = parts.nucleus_start;
+    let (base, tone) = split_vowel_and_tone(word[index]);
+    if !matches!(lower_char(base), 'i' | 'y') || lower_char(base) == replacement {
+        return None;
+    }
+    let new_base = if base.is_uppercase() {
+        replacement.to_ascii_uppercase()
+    } else {
+        replacement
+    };
+
+    let mut restyled = word.to_vec();
+    restyled[index] = apply_tone(new_base, tone)?;
+    Some(restyled)
+}
+
+// ==================== Public Functions ====================
+
+/// Moves each word's tone mark to the vowel `tone_placement` prefers
+/// (e.g. "hoà" ↔ "hòa"), using the same rules as typing. Words that are not
+/// valid Vietnamese syllables are left untouched.
+pub(crate) fn normalize_tone_placement(text: &str, tone_placement: TonePlacement) -> String {
+    rewrite_words(text, |word| retone_word(word, tone_placement))
+}
+
+/// Rewrites final i/y to one convention ("kĩ thuật" ↔ "kỹ thuật").
+pub(crate) fn normalize_i_y(text: &str, style: IYStyle) -> String {
+    rewrite_words(text, |word| restyle_i_y(word, style))
+}
@@ -0,0 +1,301 @@
+use once_cell::sync::Lazy;
+
+use crate::common::{is_vowel, lower_char, InputMethod, KeyTransformAction, WTransformKind};
+use crate::diacritics::{compose_tone, decompose_tone, split_vowel_and_tone};
+use crate::telex::is_tone_key;
+use crate::vni::vni_tone_key_to_internal;
+use crate::VitypeEngine;
+
+// ==================== Orthography Pack ====================
+
+/// An extra letter made by typing `telex_key` or `vni_key` right after `base`.
+/// A letter whose base is a vowel is a vowel too and takes the tone keys through
+/// `compose_tone`, so it has a toned form only where Unicode has a precomposed
+/// one: "ü" takes "ǘ", but ŏ takes none and a tone key after it is typed as a
+/// letter. Letters are single characters; combining sequences such as ơ̆ are not
+/// supported yet.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PackLetter {
+    pub(crate) letter: char,
+    pub(crate) base: char,
+    pub(crate) telex_key: char,
+    pub(crate) vni_key: char,
+}
+
+/// Validation rules a pack loosens so its words are not sent to foreign mode.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct ValidationRelaxations {
+    pub(crate) extra_final_consonants: Vec<String>,
+    pub(crate) allow_multiple_vowel_clusters: bool,
+}
+
+/// Letters and relaxations for a language written in an extended Vietnamese script.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct OrthographyPack {
+    pub(crate) name: String,
+    pub(crate) letters: Vec<PackLetter>,
+    pub(crate) relaxations: ValidationRelaxations,
+}
+
+impl OrthographyPack {
+    /// Parses the pack text format: `name <text>`, `letter <letter> <base> <telex key>
+    /// <vni key>`, `final <consonant>` and `relax clusters`. Lines starting with `#`
+    /// are comments. Returns `None` on any malformed line, including a final that
+    /// contains a vowel.
+    pub(crate) fn parse(text: &str) -> Option<OrthographyPack> {
+        let mut pack = OrthographyPack::default();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (directive, rest) = line.split_once(' ').unwrap_or((line, ""));
+            match directive {
+                "name" => pack.name = rest.trim().to_string(),
+                "letter" => {
+                    let fields: Vec<char> = rest
+                        .split_whitespace()
+                        .map(single_char)
+                        .collect::<Option<_>>()?;
+                    let [letter, base, telex_key, vni_key] = fields[..] else {
+                        return None;
+                    };
+                    pack.letters.push(PackLetter {
+                        letter: lower_char(letter),
+                        base: lower_char(base),
+                        telex_key: lower_char(telex_key),
+                        vni_key,
+                    });
+                }
+                "final" => {
+                    // A vowel would end the final in the syllable rules, so it could never match.
+                    let consonant = rest.trim().to_lowercase();
+                    if consonant.is_empty() || consonant.chars().any(is_vowel) {
+                        return None;
+                    }
+                    pack.relaxations.extra_final_consonants.push(consonant);
+                }
+                "relax" if rest.trim() == "clusters" => {
+                    pack.relaxations.allow_multiple_vowel_clusters = true
+                }
+                _ => return None,
+            }
+        }
+        Some(pack)
+    }
+
+    fn trigger_key(letter: &PackLetter, input_method: InputMethod) -> char {
+        match input_method {
+            InputMethod::Telex => letter.telex_key,
+            InputMethod::Vni => letter.vni_key,
+        }
+    }
+}
+
+pub(crate) static CENTRAL_HIGHLANDS_PACK: Lazy<OrthographyPack> = Lazy::new(|| {
+    OrthographyPack::parse(include_str!("../data/orthography/central_highlands.txt"))
+        .unwrap_or_default()
+});
+
+// ==================== Helper Functions ====================
+
+fn single_char(field: &str) -> Option<char> {
+    let mut chars = field.chars();
+    let ch = chars.next()?;
+    chars.next().is_none().then_some(ch)
+}
+
+fn with_case_of(template: char, ch: char) -> char {
+    if template.is_uppercase() {
+        ch.to_uppercase().next().unwrap_or(ch)
+    } else {
+        ch
+    }
+}
+
+// ==================== Orthography Methods on VitypeEngine ====================
+
+impl VitypeEngine {
+    pub(crate) fn add_orthography_pack(&mut self, pack: OrthographyPack) {
+        self.orthography_packs.push(pack);
+    }
+
+    pub(crate) fn clear_orthography_packs(&mut self) {
+        self.orthography_packs.clear();
+    }
+
+    fn pack_letters(&self) -> impl Iterator<Item = &PackLetter> {
+        self.orthography_packs
+            .iter()
+            .flat_map(|pack| pack.letters.iter())
+    }
+
+    /// The pack letter `ch` is written with, toned or not.
+    fn pack_letter_of(&self, ch: char) -> Option<&PackLetter> {
+        if self.orthography_packs.is_empty() {
+            return None;
+        }
+        // "ñ" decomposes into n and the ngã mark, so match the letter as typed first.
+        let ch = lower_char(ch);
+        let (base, _) = decompose_tone(ch);
+        self.pack_letters()
+            .find(|letter| letter.letter == ch)
+            .or_else(|| self.pack_letters().find(|letter| letter.letter == base))
+    }
+
+    /// Splits off the tone of a Vietnamese vowel or of a toned pack letter.
+    pub(super) fn split_tone_with_packs(&self, ch: char) -> (char, Option<char>) {
+        if self.orthography_packs.is_empty() {
+            return split_vowel_and_tone(ch);
+        }
+        let (base, tone) = decompose_tone(ch);
+        let is_toned_pack_letter = self
+            .pack_letter_of(ch)
+            .is_some_and(|letter| letter.letter != lower_char(ch));
+        if tone.is_some() && (is_vowel(ch) || is_toned_pack_letter) {
+            (base, tone)
+        } else {
+            (ch, None)
+        }
+    }
+
+    /// Whether `ch` is a vowel, counting the pack letters made from vowels.
+    pub(super) fn is_vowel_with_packs(&self, ch: char) -> bool {
+        is_vowel(ch)
+            || self
+                .pack_letter_of(ch)
+                .is_some_and(|letter| is_vowel(letter.base))
+    }
+
+    /// The vowel a pack letter is made from, for the cluster rules ("ŭ" → 'u').
+    pub(super) fn pack_vowel_base(&self, ch: char) -> Option<char> {
+        self.pack_letter_of(ch)
+            .map(|letter| letter.base)
+            .filter(|base| is_vowel(*base))
+    }
+
+    /// Whether the just-pushed `ch` is a tone key aimed at a pack vowel with no
+    /// toned form, such as "s" after "hŏ". The key is then typed as a letter and
+    /// the word keeps its pack letter instead of falling back to its keystrokes.
+    pub(super) fn rejects_tone_on_pack_letter(&self, ch: char) -> bool {
+        if self.orthography_packs.is_empty() || self.buffer.is_empty() {
+            return false;
+        }
+        let tone_key = match self.input_method {
+            InputMethod::Telex => Some(lower_char(ch)).filter(|key| is_tone_key(*key)),
+            InputMethod::Vni => vni_tone_key_to_internal(ch),
+        };
+        let Some(tone_key) = tone_key.filter(|key| *key != 'z') else {
+            return false;
+        };
+        let Some(vowel_index) = self.find_target_vowel_index(self.buffer.len() - 1) else {
+            return false;
+        };
+        let vowel = self.buffer[vowel_index];
+        self.pack_letter_of(vowel).is_some()
+            && compose_tone(self.get_base_vowel(vowel), Some(tone_key)).is_none()
+    }
+
+    /// Turns the letter before the just-pushed trigger key into its pack letter
+    /// ("ov" → "ŏ" in Telex, "n9" → "ñ" in VNI). A toned base keeps its tone when
+    /// the pack letter has a toned form.
+    pub(super) fn try_pack_letter_transform(&mut self, ch: char) -> Option<KeyTransformAction> {
+        if self.orthography_packs.is_empty() || self.buffer.len() < 2 {
+            return None;
+        }
+        let previous = self.buffer[self.buffer.len() - 2];
+        let (previous_base, tone) = decompose_tone(lower_char(previous));
+        let key = lower_char(ch);
+        let input_method = self.input_method;
+        let letter = self
+            .pack_letters()
+            .find(|letter| {
+                letter.base == previous_base
+                    && OrthographyPack::trigger_key(letter, input_method) == key
+            })?
+            .letter;
+        let letter = compose_tone(letter, tone)?;
+
+        self.buffer.pop();
+        let index = self.buffer.len() - 1;
+        self.buffer[index] = with_case_of(previous, letter);
+        self.last_transform_key = Some(ch);
+        self.last_w_transform_kind = WTransformKind::None;
+        Some(KeyTransformAction {
+            delete_count: 1,
+            text: self.buffer[index].to_string(),
+        })
+    }
+
+    /// Repeating the trigger key right after a pack letter restores the base letter
+    /// and the key ("ovv" → "ov"), then locks transforms like the built-in escapes.
+    pub(super) fn try_pack_letter_escape(&mut self, ch: char) -> Option<KeyTransformAction> {
+        if self.orthography_packs.is_empty() || self.last_transform_key != Some(ch) {
+            return None;
+        }
+        let last = *self.buffer.last()?;
+        let (last_letter, tone) = decompose_tone(lower_char(last));
+        let key = lower_char(ch);
+        let input_method = self.input_method;
+        let base = self
+            .pack_letters()
+            .find(|letter| {
+                letter.letter == last_letter
+                    && OrthographyPack::trigger_key(letter, input_method) == key
+            })?
+            .base;
+
+        let original = with_case_of(last, compose_tone(base, tone)?);
+        self.buffer.pop();
+        self.buffer.push(original);
+        self.buffer.push(ch);
+        self.clear_last_transform_and_suppress(key);
+        Some(KeyTransformAction {
+            delete_count: 1,
+            text: format!("{}{}", original, ch),
+        })
+    }
+
+    /// Whether the word before `before` contains a pack letter. Relaxations apply
+    /// only to such words, so plain Vietnamese and foreign words are checked as usual.
+    fn has_pack_letter(&self, before: usize) -> bool {
+        self.buffer[..before]
+            .iter()
+            .any(|ch| self.pack_letter_of(*ch).is_some())
+    }
+
+    pub(super) fn allows_multiple_vowel_clusters(&self, before: usize) -> bool {
+        self.orthography_packs
+            .iter()
+            .any(|pack| pack.relaxations.allow_multiple_vowel_clusters)
+            && self.has_pack_letter(before)
+    }
+
+    /// Whether the consonants after the last vowel of a word with pack letters form
+    /// a final that an active pack adds on top of the Vietnamese ones, or contain
+    /// one of its letters.
+    pub(super) fn has_pack_final_consonant(&self, before: usize) -> bool {
+        if !self.has_pack_letter(before) {
+            return false;
+        }
+        let Some(last_vowel_index) = self.last_effective_vowel_index(before) else {
+            return false;
+        };
+        let tail: String = self.buffer[last_vowel_index + 1..before]
+            .iter()
+            .map(|ch| lower_char(*ch))
+            .collect();
+        if tail.is_empty() || tail.chars().any(is_vowel) {
+            return false;
+        }
+        if tail
+            .chars()
+            .any(|ch| self.pack_letters().any(|letter| letter.letter == ch))
+        {
+            return true;
+        }
+        self.orthography_packs
+            .iter()
+            .any(|pack| pack.relaxations.extra_final_consonants.contains(&tail))
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::common::{lower_char, KeyTransformAction};
+use crate::diacritics::split_vowel_and_tone;
+use crate::lexicon::{Lexicon, DEFAULT_LEXICON};
+use crate::syllable::match_case;
+use crate::{HistorySegment, VitypeEngine};
+
+// ==================== Prediction ====================
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum PredictionKind {
+    /// Finishes the syllable being typed.
+    Completion,
+    /// A likely syllable after the last committed word.
+    NextSyllable,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Prediction {
+    pub(crate) text: String,
+    pub(crate) kind: PredictionKind,
+    pub(crate) probability: f64,
+}
+
+/// The prediction model and the suggestions last shown to the host. Rejected
+/// words stay hidden until the next keystroke changes the context.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PredictionState {
+    model: Option<Arc<Lexicon>>,
+    suggestions: Vec<Prediction>,
+    rejected: HashSet<String>,
+}
+
+// ==================== Helper Functions ====================
+
+/// Strips tone, shape and stroke so "nguo" can match "người".
+fn plain_letter(ch: char) -> char {
+    let (base, _) = split_vowel_and_tone(lower_char(ch));
+    match base {
+        'ă' | 'â' => 'a',
+        'ê' => 'e',
+        'ô' | 'ơ' => 'o',
+        'ư' => 'u',
+        'đ' => 'd',
+        other => other,
+    }
+}
+
+/// Whether `word` can still be reached from `typed`: each typed letter is either
+/// the word's letter or that letter without its marks.
+fn is_completion_of(typed: &[char], word: &str) -> bool {
+    let word: Vec<char> = word.chars().collect();
+    word.len() > typed.len()
+        && typed.iter().zip(word.iter()).all(|(typed, target)| {
+            let typed = lower_char(*typed);
+            typed == *target || typed == plain_letter(*target)
+        })
+}
+
+// ==================== Prediction Methods on VitypeEngine ====================
+
+impl VitypeEngine {
+    /// Replaces the bundled model, e.g. with one trained from the user's corpus.
+    pub(crate) fn set_prediction_model(&mut self, model: Lexicon) {
+        self.prediction.model = Some(Arc::new(model));
+    }
+
+    pub(super) fn copy_prediction_model_from(&mut self, other: &VitypeEngine) {
+        self.prediction.model = other.prediction.model.clone();
+    }
+
+    pub(super) fn clear_predictions(&mut self) {
+        self.prediction.suggestions.clear();
+        self.prediction.rejected.clear();
+    }
+
+    /// The last committed word, when only whitespace separates it from the caret.
+    fn prediction_context(&self) -> Option<String> {
+        let mut segments = self.history.iter().rev();
+        let mut segment = segments.next()?;
+        if let HistorySegment::Boundary(boundary) = segment {
+            if !boundary.iter().all(|ch| ch.is_whitespace()) {
+                return None;
+            }
+            segment = segments.next()?;
+        } else if self.buffer.is_empty() {
+            return None;
+        }
+        match segment {
+            HistorySegment::Word(word) => {
+                Some(word.buffer.iter().map(|ch| lower_char(*ch)).collect())
+            }
+            HistorySegment::Boundary(_) => None,
+        }
+    }
+
+    /// Ranks completions of the current word, or next syllables when no word is
+    /// being typed, and remembers them for `accept_prediction`.
+    pub(crate) fn predictions(&mut self, limit: usize) -> &[Prediction] {
+        let model = self.prediction.model.as_deref().unwrap_or(&DEFAULT_LEXICON);
+        let previous = self.prediction_context();
+
+        let mut found: Vec<Prediction> = if self.buffer.is_empty() {
+            match &previous {
+                Some(previous) => model
+                    .followers(previous)
+                    .map(|(word, _)| Prediction {
+                        text: word.to_string(),
+                        kind: PredictionKind::NextSyllable,
+                        probability: model.conditional_probability(Some(previous), word),
+                    })
+                    .collect(),
+                None => Vec::new(),
+            }
+        } else {
+            model
+                .unigrams()
+                .filter(|(word, _)| is_completion_of(&self.buffer, word))
+                .map(|(word, _)| Prediction {
+                    text: match_case(&self.buffer, word),
+                    kind: PredictionKind::Completion,
+                    probability: model.conditional_probability(previous.as_deref(), word),
+                })
+                .collect()
+        };
+
+        found.retain(|prediction| {
+            !self
+                .prediction
+                .rejected
+                .contains(&prediction.text.to_lowercase())
+        });
+        found.sort_by(|a, b| {
+            b.probability
+                .total_cmp(&a.probability)
+                .then_with(|| a.text.cmp(&b.text))
+        });
+        found.truncate(limit);
+        self.prediction.suggestions = found;
+        &self.prediction.suggestions
+    }
+
+    /// Types the suggestion at `index` followed by a space, committing it to
+    /// history so the next prediction can use it as context.
+    pub(crate) fn accept_prediction(&mut self, index: usize) -> Option<KeyTransformAction> {
+        let prediction = self.prediction.suggestions.get(index)?.clone();
+        let delete_count = self.buffer.len();
+        let chars: Vec<char> = prediction.text.chars().collect();
+
+        self.buffer = chars.clone();
+        self.raw_buffer = chars;
+        self.reset_provenance();
+        self.transforms_locked = true;
+        self.commit_current_word_to_history_if_needed();
+        self.push_boundary_to_history(' ');
+        self.reset_current_word();
+        self.clear_predictions();
+
+        Some(KeyTransformAction {
+            delete_count,
+            text: format!("{} ", prediction.text),
+        })
+    }
+
+    /// Hides the suggestion at `index` until the context changes. Nothing has been
+    /// typed for a suggestion, so there is never text to remove.
+    pub(crate) fn reject_prediction(&mut self, index: usize) -> Option<KeyTransformAction> {
+        let prediction = self.prediction.suggestions.get(index)?.clone();
+        self.prediction.suggestions.remove(index);
+        self.prediction
+            .rejected
+            .insert(prediction.text.to_lowercase());
+        None
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{is_word_boundary, VitypeEngine};
+
+// ==================== Preedit Update ====================
+
+/// What a host using marked text should show after a key. `commit` is final text
+/// to insert before the composing string; nothing is ever deleted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct PreeditUpdate {
+    pub(crate) preedit: String,
+    /// Caret offset inside `preedit`, in characters.
+    pub(crate) caret: usize,
+    pub(crate) commit: String,
+}
+
+// ==================== Preedit Methods on VitypeEngine ====================
+
+impl VitypeEngine {
+    fn preedit_update(&self, commit: String) -> PreeditUpdate {
+        PreeditUpdate {
+            preedit: self.buffer.iter().collect(),
+            caret: self.buffer.len(),
+            commit,
+        }
+    }
+
+    /// `process` for hosts that compose in marked text. A boundary commits the
+    /// finished word followed by the boundary; other keys only change the composing
+    /// string. Rewrites of words committed earlier (e.g. when a URL is recognised)
+    /// cannot be expressed here and are dropped.
+    pub(crate) fn process_preedit(&mut self, input: &str) -> PreeditUpdate {
+        let mut chars = input.chars();
+        let (Some(ch), None) = (chars.next(), chars.next()) else {
+            return self.preedit_update(String::new());
+        };
+        if !is_word_boundary(ch, self.input_method) {
+            let _ = self.process(input);
+            return self.preedit_update(String::new());
+        }
+
+        let mut committed = self.buffer.clone();
+        let word_raw_length = if committed.is_empty() {
+            0
+        } else {
+            self.raw_buffer.len()
+        };
+        match self.process(input) {
+            None => committed.push(ch),
+            Some(action) => {
+                let text: Vec<char> = action.text.chars().collect();
+                if action.delete_count <= committed.len() {
+                    committed.truncate(committed.len() - action.delete_count);
+                    committed.extend(text);
+                } else {
+                    // The action also rewrites earlier words; this word was restored
+                    // to its keystrokes and is followed by the boundary.
+                    let keep = (word_raw_length + 1).min(text.len());
+                    committed = text[text.len() - keep..].to_vec();
+                }
+            }
+        }
+        self.preedit_update(committed.into_iter().collect())
+    }
+
+    /// Commits the composing string as it is, without a boundary.
+    pub(crate) fn commit_preedit(&mut self) -> PreeditUpdate {
+        let commit: String = self.buffer.iter().collect();
+        self.dismiss_han_nom_candidates();
+        self.clear_predictions();
+        self.commit_current_word_to_history_if_needed();
+        self.reset_current_word();
+        self.preedit_update(commit)
+    }
+
+    /// Backspace inside the composing string. Returns `None` when there is nothing
+    /// composed; the host then deletes committed text itself, and the engine forgets
+    /// its history because that text can no longer be recomposed.
+    pub(crate) fn delete_preedit_character(&mut self) -> Option<PreeditUpdate> {
+        if self.buffer.is_empty() {
+            self.reset();
+            return None;
+        }
+        self.delete_last_character();
+        Some(self.preedit_update(String::new()))
+    }
+}
@@ -0,0 +1,402 @@
+use crate::common::WTransformKind;
+use crate::{HistorySegment, VitypeEngine};
+
+// ==================== Keystroke Provenance ====================
+//
+// `provenance[i]` lists the indices into `raw_buffer` of the keystrokes that
+// produced `buffer[i]`: "vieejt" gives [[0], [1], [2, 3, 4], [5]]. Each keystroke
+// is diffed against the previous output, so a key that changes a letter in place
+// (a tone, a shape, đ) joins that letter's list and a key that adds letters owns
+// them. Every raw keystroke belongs to at least one output char.
+//
+// Alongside it, `word_snapshots` keeps the word state after each of the latest
+// keystrokes, so backspace can go back to an earlier state, transform keys
+// included, without replaying anything.
+
+/// Snapshots kept per word. Each one copies the word, so longer tokens (URLs,
+/// identifiers) keep only their newest states and backspace past them falls back
+/// to provenance.
+pub(crate) const MAX_WORD_SNAPSHOTS: usize = 32;
+
+#[derive(Clone)]
+pub(crate) struct WordSnapshot {
+    buffer: Vec<char>,
+    raw_buffer: Vec<char>,
+    provenance: Vec<Vec<usize>>,
+    is_foreign_mode: bool,
+    transforms_locked: bool,
+    last_transform_key: Option<char>,
+    last_w_transform_kind: WTransformKind,
+    suppressed_transform_key: Option<char>,
+}
+
+/// One keystroke per char, for words whose output is their own keystrokes.
+pub(crate) fn identity_provenance(len: usize) -> Vec<Vec<usize>> {
+    (0..len).map(|index| vec![index]).collect()
+}
+
+/// Every keystroke for every char, for words replaced as a whole by text with no
+/// per-letter link to the keys, such as a Hán Nôm character.
+pub(crate) fn whole_word_provenance(buffer_len: usize, raw_len: usize) -> Vec<Vec<usize>> {
+    vec![(0..raw_len).collect(); buffer_len]
+}
+
+/// Spreads `raw_len` keystrokes over `buffer_len` chars in order. This is only a
+/// positional guess, the last resort when nothing better is known; snapshots
+/// taken before it do not match it.
+pub(crate) fn default_provenance(buffer_len: usize, raw_len: usize) -> Vec<Vec<usize>> {
+    if buffer_len == 0 {
+        return Vec::new();
+    }
+    if raw_len == 0 {
+        return vec![Vec::new(); buffer_len];
+    }
+    (0..buffer_len)
+        .map(|index| {
+            let start = (index * raw_len / buffer_len).min(raw_len - 1);
+            let end = if index + 1 == buffer_len {
+                raw_len
+            } else {
+                ((index + 1) * raw_len / buffer_len).max(start + 1)
+            };
+            (start..end).collect()
+        })
+        .collect()
+}
+
+/// Carries `old_provenance` over to `new`. Chars outside the changed middle keep
+/// their keystrokes. A changed char keeps the key that typed its letter and
+/// shares the mark keys of every changed char, plus `key` unless the key added
+/// chars of its own, so a tone that moves between vowels stays with both.
+fn diff_provenance(
+    old: &[char],
+    old_provenance: &[Vec<usize>],
+    new: &[char],
+    key: Option<usize>,
+) -> Vec<Vec<usize>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = prefix..old.len() - suffix;
+    let new_middle = prefix..new.len() - suffix;
+    let common = old_middle.len().min(new_middle.len());
+
+    let mut shared: Vec<usize> = Vec::new();
+    let mut changed = Vec::new();
+    for offset in 0..common {
+        let index = prefix + offset;
+        if old[index] != new[index] {
+            shared.extend(old_provenance[index].iter().skip(1));
+            changed.push(offset);
+        }
+    }
+    // Chars that disappeared hand their keystrokes to whatever changed.
+    for keys in &old_provenance[old_middle.start + common..old_middle.end] {
+        shared.extend(keys);
+    }
+    let adds_chars = new_middle.len() > common;
+    if let Some(key) = key {
+        if !adds_chars {
+            shared.push(key);
+        }
+    }
+    shared.sort_unstable();
+    shared.dedup();
+
+    let mut provenance: Vec<Vec<usize>> = old_provenance[..prefix].to_vec();
+    for offset in 0..common {
+        if changed.contains(&offset) {
+            let own = &old_provenance[prefix + offset];
+            let mut keys: Vec<usize> = own.iter().take(1).chain(&shared).copied().collect();
+            keys.sort_unstable();
+            keys.dedup();
+            provenance.push(keys);
+        } else {
+            provenance.push(old_provenance[prefix + offset].clone());
+        }
+    }
+    // An escape pushes a char without adding a keystroke: it reuses the last key,
+    // which moves to the pushed char even when an unchanged char had absorbed it.
+    let escaped_key = match key {
+        None if adds_chars && shared.is_empty() => old_provenance.iter().flatten().max().copied(),
+        _ => None,
+    };
+    for _ in common..new_middle.len() {
+        match (key, escaped_key) {
+            (Some(key), _) if !shared.contains(&key) => provenance.push(vec![key]),
+            (None, Some(escaped_key)) => provenance.push(vec![escaped_key]),
+            _ => provenance.push(shared.clone()),
+        }
+    }
+    provenance.extend_from_slice(&old_provenance[old_middle.end..]);
+    if let Some(escaped_key) = escaped_key {
+        for keys in &mut provenance[..new_middle.start + common] {
+            if keys.len() > 1 {
+                keys.retain(|index| *index != escaped_key);
+            }
+        }
+    }
+
+    // Keystrokes with nowhere else to go (a removed char, a key that changed
+    // nothing visible) stay with the char before the edit.
+    let mut orphaned: Vec<usize> = shared;
+    if let Some(key) = key {
+        orphaned.push(key);
+    }
+    orphaned.retain(|index| !provenance.iter().any(|keys| keys.contains(index)));
+    if !orphaned.is_empty() && !provenance.is_empty() {
+        let target = prefix.min(provenance.len() - 1);
+        let target = if prefix > 0 && new_middle.is_empty() {
+            prefix - 1
+        } else {
+            target
+        };
+        provenance[target].extend(orphaned);
+        provenance[target].sort_unstable();
+        provenance[target].dedup();
+    }
+    provenance
+}
+
+impl VitypeEngine {
+    /// Updates provenance and snapshots after `process_key` handled one key.
+    pub(super) fn record_keystroke(&mut self, old_buffer: &[char], old_raw: &[char]) {
+        if self.buffer.is_empty() {
+            self.provenance.clear();
+            self.word_snapshots.clear();
+            return;
+        }
+
+        let raw_len = self.raw_buffer.len();
+        let is_one_key = self.provenance.len() == old_buffer.len()
+            && self.raw_buffer.starts_with(old_raw)
+            && raw_len <= old_raw.len() + 1;
+        if self.buffer == self.raw_buffer {
+            self.provenance = identity_provenance(raw_len);
+        } else if !is_one_key {
+            self.provenance = default_provenance(self.buffer.len(), raw_len);
+            self.word_snapshots.clear();
+        } else {
+            let key = (raw_len > old_raw.len()).then(|| raw_len - 1);
+            self.provenance = diff_provenance(old_buffer, &self.provenance, &self.buffer, key);
+        }
+
+        let raw = &self.raw_buffer;
+        self.word_snapshots.retain(|snapshot| {
+            raw.starts_with(&snapshot.raw_buffer) && snapshot.raw_buffer != *raw
+        });
+        self.push_word_snapshot();
+    }
+
+    /// Sets a fresh provenance for a word whose output was replaced as a whole and
+    /// drops the snapshots, which describe the word before the replacement.
+    pub(super) fn reset_provenance(&mut self) {
+        self.provenance = fallback_provenance(&self.buffer, &self.raw_buffer);
+        self.word_snapshots.clear();
+    }
+
+    pub(super) fn push_word_snapshot(&mut self) {
+        if self.word_snapshots.len() >= MAX_WORD_SNAPSHOTS {
+            self.word_snapshots.remove(0);
+        }
+        self.word_snapshots.push(WordSnapshot {
+            buffer: self.buffer.clone(),
+            raw_buffer: self.raw_buffer.clone(),
+            provenance: self.provenance.clone(),
+            is_foreign_mode: self.is_foreign_mode,
+            transforms_locked: self.transforms_locked,
+            last_transform_key: self.last_transform_key,
+            last_w_transform_kind: self.last_w_transform_kind,
+            suppressed_transform_key: self.suppressed_transform_key,
+        });
+    }
+
+    fn restore_word_snapshot(&mut self, snapshot: WordSnapshot) {
+        self.buffer = snapshot.buffer;
+        self.raw_buffer = snapshot.raw_buffer;
+        self.provenance = snapshot.provenance;
+        self.is_foreign_mode = snapshot.is_foreign_mode;
+        self.transforms_locked = snapshot.transforms_locked;
+        self.last_transform_key = snapshot.last_transform_key;
+        self.last_w_transform_kind = snapshot.last_w_transform_kind;
+        self.suppressed_transform_key = snapshot.suppressed_transform_key;
+    }
+
+    /// Removes the last output char together with the keystrokes that produced
+    /// only it. A snapshot with exactly the remaining output wins, since it also
+    /// restores the transform keys.
+    pub(super) fn delete_last_character_in_current_word(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+
+        let mut desired_buffer = self.buffer.clone();
+        desired_buffer.pop();
+
+        if desired_buffer.is_empty() {
+            self.reset_current_word();
+            return;
+        }
+
+        if let Some(index) = self.word_snapshots.iter().rposition(|snapshot| {
+            snapshot.buffer == desired_buffer && self.raw_buffer.starts_with(&snapshot.raw_buffer)
+        }) {
+            self.word_snapshots.truncate(index + 1);
+            let snapshot = self.word_snapshots[index].clone();
+            self.restore_word_snapshot(snapshot);
+            return;
+        }
+
+        if self.provenance.len() != self.buffer.len() {
+            self.reset_provenance();
+        }
+        let removed = self.provenance.pop().unwrap_or_default();
+        let mut dropped: Vec<usize> = removed
+            .iter()
+            .copied()
+            .filter(|index| !self.provenance.iter().any(|keys| keys.contains(index)))
+            .collect();
+        if dropped.is_empty() {
+            // Every keystroke also shaped an earlier char: drop only the latest.
+            dropped.extend(removed.iter().max());
+        }
+
+        let mut renumbered = Vec::with_capacity(self.raw_buffer.len());
+        let mut raw_buffer = Vec::with_capacity(self.raw_buffer.len());
+        for (index, ch) in self.raw_buffer.iter().enumerate() {
+            if dropped.contains(&index) {
+                renumbered.push(None);
+            } else {
+                renumbered.push(Some(raw_buffer.len()));
+                raw_buffer.push(*ch);
+            }
+        }
+        for keys in &mut self.provenance {
+            *keys = keys.iter().filter_map(|index| renumbered[*index]).collect();
+        }
+
+        self.buffer = desired_buffer;
+        self.raw_buffer = raw_buffer;
+        if self.provenance.iter().any(Vec::is_empty) {
+            self.reset_provenance();
+        }
+        self.clear_transform_state();
+        self.is_foreign_mode =
+            !self.is_vietnamese_forced() && self.is_invalid_vietnamese_syllable(self.buffer.len());
+
+        let raw = &self.raw_buffer;
+        self.word_snapshots.retain(|snapshot| {
+            raw.starts_with(&snapshot.raw_buffer) && snapshot.raw_buffer != *raw
+        });
+        self.push_word_snapshot();
+    }
+
+    /// Checks that provenance and snapshots describe the current word and the
+    /// words in history. Returns the first problem found.
+    pub(crate) fn check_provenance(&self) -> Result<(), String> {
+        check_word(&self.buffer, &self.raw_buffer, &self.provenance)
+            .map_err(|problem| format!("current word: {problem}"))?;
+        for snapshot in &self.word_snapshots {
+            if !self.raw_buffer.starts_with(&snapshot.raw_buffer) {
+                return Err(format!(
+                    "snapshot {:?} is not a prefix of {:?}",
+                    snapshot.raw_buffer, self.raw_buffer
+                ));
+            }
+            check_word(&snapshot.buffer, &snapshot.raw_buffer, &snapshot.provenance)
+                .map_err(|problem| format!("snapshot: {problem}"))?;
+        }
+        for segment in self.history.iter().chain(&self.following) {
+            if let HistorySegment::Word(word) = segment {
+                check_word(&word.buffer, &word.raw_buffer, &word.provenance)
+                    .map_err(|problem| format!("history word: {problem}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs `check_provenance` after each key in debug builds. Tests fail on a
+    /// mismatch; other debug builds repair the words so a bug costs at most a less
+    /// precise backspace. Release builds skip the walk; `repair_provenance` stays
+    /// available to call explicitly.
+    pub(super) fn repair_provenance_if_needed(&mut self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        if let Err(problem) = self.repair_provenance() {
+            if cfg!(test) {
+                panic!("keystroke provenance out of sync: {problem}");
+            }
+        }
+    }
+
+    /// Gives every word that fails `check_provenance` a fallback provenance and
+    /// drops unusable snapshots. Returns the problem that was found, if any.
+    pub(crate) fn repair_provenance(&mut self) -> Result<(), String> {
+        let result = self.check_provenance();
+        if result.is_err() {
+            if check_word(&self.buffer, &self.raw_buffer, &self.provenance).is_err() {
+                self.provenance = fallback_provenance(&self.buffer, &self.raw_buffer);
+            }
+            let raw = &self.raw_buffer;
+            if !self.word_snapshots.iter().all(|snapshot| {
+                raw.starts_with(&snapshot.raw_buffer)
+                    && check_word(&snapshot.buffer, &snapshot.raw_buffer, &snapshot.provenance)
+                        .is_ok()
+            }) {
+                self.word_snapshots.clear();
+            }
+            for segment in self.history.iter_mut().chain(self.following.iter_mut()) {
+                if let HistorySegment::Word(word) = segment {
+                    if check_word(&word.buffer, &word.raw_buffer, &word.provenance).is_err() {
+                        word.provenance = fallback_provenance(&word.buffer, &word.raw_buffer);
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+fn fallback_provenance(buffer: &[char], raw: &[char]) -> Vec<Vec<usize>> {
+    if buffer == raw {
+        identity_provenance(raw.len())
+    } else {
+        default_provenance(buffer.len(), raw.len())
+    }
+}
+
+fn check_word(buffer: &[char], raw: &[char], provenance: &[Vec<usize>]) -> Result<(), String> {
+    if provenance.len() != buffer.len() {
+        return Err(format!(
+            "{} provenance entries for {:?}",
+            provenance.len(),
+            buffer.iter().collect::<String>()
+        ));
+    }
+    let mut covered = vec![false; raw.len()];
+    for (ch, keys) in buffer.iter().zip(provenance) {
+        if keys.is_empty() && !raw.is_empty() {
+            return Err(format!("{ch:?} has no keystrokes"));
+        }
+        if keys.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(format!("{ch:?} has unordered keystrokes {keys:?}"));
+        }
+        for &index in keys {
+            *covered
+                .get_mut(index)
+                .ok_or_else(|| format!("{ch:?} points past the raw buffer: {index}"))? = true;
+        }
+    }
+    match covered.iter().position(|covered| !covered) {
+        Some(index) if !buffer.is_empty() => Err(format!(
+            "keystroke {:?} at {index} produced nothing",
+            raw[index]
+        )),
+        _ => Ok(()),
+    }
+}
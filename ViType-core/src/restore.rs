@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+
+use crate::common::{lower_char, TonePlacement};
+use crate::diacritics::{apply_shape, VowelShape};
+use crate::lexicon::Lexicon;
+use crate::syllable::{
+    compose_syllable, match_case, word_tokens, CODAS, NUCLEI, ONSETS, TONE_KEYS,
+};
+use crate::VitypeEngine;
+
+// ==================== Restoration ====================
+
+/// One restored token; spans refer to the input text. `confidence` is the share of
+/// the decoder's score that went to the chosen candidate (1.0 for untouched tokens).
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct RestoredToken {
+    pub(crate) byte_start: usize,
+    pub(crate) byte_end: usize,
+    pub(crate) char_start: usize,
+    pub(crate) char_end: usize,
+    pub(crate) text: String,
+    pub(crate) confidence: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Restoration {
+    pub(crate) text: String,
+    pub(crate) tokens: Vec<RestoredToken>,
+}
+
+// ==================== Helper Functions ====================
+
+fn letter_options(ch: char) -> Vec<char> {
+    if ch == 'd' {
+        return vec!['d', 'đ'];
+    }
+    let mut options = vec![ch];
+    for shape in [VowelShape::Circumflex, VowelShape::Horn, VowelShape::Breve] {
+        if let Some(shaped) = apply_shape(ch, shape) {
+            if !options.contains(&shaped) {
+                options.push(shaped);
+            }
+        }
+    }
+    options
+}
+
+/// Indices into `letter_options` of each letter of `spelling`, or `None` when
+/// `spelling` does not strip down to `letters`.
+fn option_indices(spelling: &[char], letters: &[char]) -> Option<Vec<usize>> {
+    spelling
+        .iter()
+        .zip(letters)
+        .map(|(shaped, letter)| {
+            letter_options(*letter)
+                .iter()
+                .position(|option| option == shaped)
+        })
+        .collect()
+}
+
+/// Every valid syllable that strips down to the lowercase ASCII `word`, with the
+/// unaccented spelling first when it is itself valid. Spellings come from the
+/// onset, nucleus and coda tables, so the work does not grow with the word.
+fn syllable_candidates(word: &str, tone_placement: TonePlacement) -> Vec<String> {
+    let letters: Vec<char> = word.chars().collect();
+    let mut spellings: Vec<(Vec<usize>, Vec<char>)> = Vec::new();
+    for onset in ONSETS {
+        let onset: Vec<char> = onset.chars().collect();
+        if onset.len() > letters.len() || option_indices(&onset, &letters).is_none() {
+            continue;
+        }
+        for (nucleus, _) in NUCLEI {
+            let mut stem = onset.clone();
+            stem.extend(nucleus.chars());
+            if stem.len() > letters.len() || option_indices(&stem, &letters).is_none() {
+                continue;
+            }
+            for coda in CODAS {
+                let mut spelling = stem.clone();
+                spelling.extend(coda.chars());
+                if spelling.len() != letters.len() {
+                    continue;
+                }
+                if let Some(indices) = option_indices(&spelling, &letters) {
+                    spellings.push((indices, spelling));
+                }
+            }
+        }
+    }
+    // Option order puts each plain letter first, as a letter-by-letter product would.
+    spellings.sort();
+    spellings.dedup();
+
+    let mut candidates: Vec<String> = Vec::new();
+    for (_, spelling) in spellings {
+        let Some(parts) = VitypeEngine::for_word(&spelling, tone_placement).split_syllable() else {
+            continue;
+        };
+        let onset: String = parts.onset.iter().collect();
+        let coda: String = parts.coda.iter().collect();
+        for tone in TONE_KEYS {
+            if let Some(candidate) =
+                compose_syllable(&onset, &parts.nucleus, &coda, tone, tone_placement)
+            {
+                if !candidates.contains(&candidate) {
+                    candidates.push(candidate);
+                }
+            }
+        }
+    }
+    candidates
+}
+
+fn is_restorable(chars: &[char]) -> bool {
+    chars.iter().all(|ch| ch.is_ascii_alphabetic())
+        && !chars
+            .iter()
+            .any(|ch| matches!(lower_char(*ch), 'f' | 'j' | 'w' | 'z'))
+}
+
+fn log_sum_exp(values: &[f64]) -> f64 {
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    max + values
+        .iter()
+        .map(|value| (value - max).exp())
+        .sum::<f64>()
+        .ln()
+}
+
+/// Viterbi decoding over one run of adjacent syllables. Returns the chosen
+/// candidate index and its max-marginal confidence for each position.
+fn decode_chain(candidates: &[Vec<String>], lexicon: &Lexicon) -> Vec<(usize, f64)> {
+    let length = candidates.len();
+    let transition =
+        |previous: Option<&str>, word: &str| lexicon.conditional_probability(previous, word).ln();
+
+    // Forward pass: best score of any path ending in each candidate.
+    let mut forward: Vec<Vec<f64>> = Vec::with_capacity(length);
+    let mut back_pointers: Vec<Vec<usize>> = Vec::with_capacity(length);
+    for (position, options) in candidates.iter().enumerate() {
+        let mut scores = Vec::with_capacity(options.len());
+        let mut pointers = Vec::with_capacity(options.len());
+        for word in options {
+            if position == 0 {
+                scores.push(transition(None, word));
+                pointers.push(0);
+                continue;
+            }
+            let mut best = (f64::NEG_INFINITY, 0usize);
+            for (previous_index, previous) in candidates[position - 1].iter().enumerate() {
+                let score =
+                    forward[position - 1][previous_index] + transition(Some(previous), word);
+                if score > best.0 {
+                    best = (score, previous_index);
+                }
+            }
+            scores.push(best.0);
+            pointers.push(best.1);
+        }
+        forward.push(scores);
+        back_pointers.push(pointers);
+    }
+
+    // Backward pass: best score of any continuation after each candidate.
+    let mut backward: Vec<Vec<f64>> = vec![Vec::new(); length];
+    for position in (0..length).rev() {
+        backward[position] = candidates[position]
+            .iter()
+            .map(|word| {
+                if position + 1 == length {
+                    return 0.0;
+                }
+                candidates[position + 1]
+                    .iter()
+                    .enumerate()
+                    .map(|(next_index, next)| {
+                        transition(Some(word), next) + backward[position + 1][next_index]
+                    })
+                    .fold(f64::NEG_INFINITY, f64::max)
+            })
+            .collect();
+    }
+
+    let mut chosen = vec![0usize; length];
+    if let Some(last) = forward.last() {
+        let mut best_index = 0;
+        for (index, score) in last.iter().enumerate() {
+            if *score > last[best_index] {
+                best_index = index;
+            }
+        }
+        chosen[length - 1] = best_index;
+        for position in (1..length).rev() {
+            chosen[position - 1] = back_pointers[position][chosen[position]];
+        }
+    }
+
+    chosen
+        .iter()
+        .enumerate()
+        .map(|(position, index)| {
+            let marginals: Vec<f64> = forward[position]
+                .iter()
+                .zip(backward[position].iter())
+                .map(|(forward_score, backward_score)| forward_score + backward_score)
+                .collect();
+            let confidence = (marginals[*index] - log_sum_exp(&marginals)).exp();
+            (*index, confidence)
+        })
+        .collect()
+}
+
+/// Restores tones, shapes and đ in unaccented Vietnamese text. Only pure ASCII
+/// tokens are touched; a run of syllables separated by plain whitespace is decoded
+/// as one sequence so each word is chosen in context.
+pub(crate) fn restore_diacritics(
+    text: &str,
+    lexicon: &Lexicon,
+    tone_placement: TonePlacement,
+) -> Restoration {
+    let tokens = word_tokens(text);
+    let mut cache: HashMap<String, Vec<String>> = HashMap::new();
+    let mut candidates: Vec<Vec<String>> = Vec::with_capacity(tokens.len());
+    for token in &tokens {
+        if !is_restorable(&token.chars) {
+            candidates.push(Vec::new());
+            continue;
+        }
+        let lower: String = token.chars.iter().map(|ch| lower_char(*ch)).collect();
+        let options = cache
+            .entry(lower.clone())
+            .or_insert_with(|| syllable_candidates(&lower, tone_placement))
+            .clone();
+        candidates.push(options);
+    }
+
+    let mut choices: Vec<Option<(usize, f64)>> = vec![None; tokens.len()];
+    let mut start = 0;
+    while start < tokens.len() {
+        if candidates[start].is_empty() {
+            start += 1;
+            continue;
+        }
+        let mut end = start + 1;
+        while end < tokens.len()
+            && !candidates[end].is_empty()
+            && text[tokens[end - 1].byte_end..tokens[end].byte_start]
+                .chars()
+                .all(char::is_whitespace)
+        {
+            end += 1;
+        }
+        for (offset, choice) in decode_chain(&candidates[start..end], lexicon)
+            .into_iter()
+            .enumerate()
+        {
+            choices[start + offset] = Some(choice);
+        }
+        start = end;
+    }
+
+    let mut restored = String::with_capacity(text.len());
+    let mut restored_tokens = Vec::with_capacity(tokens.len());
+    let mut cursor = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        restored.push_str(&text[cursor..token.byte_start]);
+        let original: String = token.chars.iter().collect();
+        let (word, confidence) = match choices[index] {
+            Some((choice, confidence)) => (
+                match_case(&token.chars, &candidates[index][choice]),
+                confidence,
+            ),
+            None => (original, 1.0),
+        };
+        restored.push_str(&word);
+        restored_tokens.push(RestoredToken {
+            byte_start: token.byte_start,
+            byte_end: token.byte_end,
+            char_start: token.char_start,
+            char_end: token.char_end,
+            text: word,
+            confidence,
+        });
+        cursor = token.byte_end;
+    }
+    restored.push_str(&text[cursor..]);
+
+    Restoration {
+        text: restored,
+        tokens: restored_tokens,
+    }
+}
@@ -0,0 +1,189 @@
+use crate::VitypeEngine;
+
+/// Sessions kept before the least recently activated one is dropped.
+const DEFAULT_MAX_SESSIONS: usize = 8;
+
+// ==================== Session Manager ====================
+//
+// One engine per host context (a window, a text field), so leaving a field and
+// coming back keeps the word in progress and the backspace-restore history.
+// Settings live on a configuration engine that is never typed into; a session
+// takes them over when it is activated with an older configuration than the
+// current one, so every session shares them without copying on each switch.
+// Settings changed on a session's own engine are not shared and are replaced
+// at the next configuration change.
+
+struct Session {
+    context_id: String,
+    engine: Box<VitypeEngine>,
+    /// The configuration generation last copied in; `None` for a new session.
+    configuration_generation: Option<u64>,
+}
+
+pub(crate) struct SessionManager {
+    configuration: VitypeEngine,
+    /// Bumped whenever the configuration may have changed.
+    configuration_generation: u64,
+    /// Least recently activated first; the active session, if any, is last.
+    sessions: Vec<Session>,
+    has_active: bool,
+    max_sessions: usize,
+}
+
+impl SessionManager {
+    pub(crate) fn new() -> Self {
+        SessionManager {
+            configuration: VitypeEngine::new(),
+            configuration_generation: 0,
+            sessions: Vec::new(),
+            has_active: false,
+            max_sessions: DEFAULT_MAX_SESSIONS,
+        }
+    }
+
+    /// The engine whose settings every session uses. Changes reach the active
+    /// session once applied and the others when they are next activated.
+    pub(crate) fn configuration_mut(&mut self) -> &mut VitypeEngine {
+        self.configuration_generation += 1;
+        &mut self.configuration
+    }
+
+    /// Pushes the configuration to the active session after it was changed.
+    /// Hosts holding the configuration pointer change it without going through
+    /// `configuration_mut`, so this marks every other session stale as well.
+    pub(crate) fn apply_configuration(&mut self) {
+        self.configuration_generation += 1;
+        if !self.has_active {
+            return;
+        }
+        if let Some(session) = self.sessions.last_mut() {
+            Self::refresh_configuration(
+                session,
+                &self.configuration,
+                self.configuration_generation,
+            );
+        }
+    }
+
+    pub(crate) fn set_max_sessions(&mut self, limit: usize) {
+        self.max_sessions = limit.max(1);
+        self.evict_to_limit();
+    }
+
+    pub(crate) fn session_count(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Switches to the session for `context_id`, creating it when needed, and
+    /// returns its engine. Candidate lists and suggestions of the session being
+    /// left are dismissed, since the host no longer shows them.
+    pub(crate) fn activate(&mut self, context_id: &str) -> &mut VitypeEngine {
+        let already_active = self.has_active
+            && self
+                .sessions
+                .last()
+                .is_some_and(|session| session.context_id == context_id);
+        if !already_active {
+            if let Some(engine) = self.active_engine_mut() {
+                engine.dismiss_han_nom_candidates();
+                engine.clear_predictions();
+            }
+            let session = match self
+                .sessions
+                .iter()
+                .position(|session| session.context_id == context_id)
+            {
+                Some(index) => self.sessions.remove(index),
+                None => Session {
+                    context_id: context_id.to_string(),
+                    engine: Box::new(VitypeEngine::new()),
+                    configuration_generation: None,
+                },
+            };
+            self.sessions.push(session);
+            self.has_active = true;
+            self.evict_to_limit();
+        }
+
+        let session = self.sessions.last_mut().expect("session was just pushed");
+        Self::refresh_configuration(session, &self.configuration, self.configuration_generation);
+        &mut session.engine
+    }
+
+    /// Leaves the active session without dropping it.
+    pub(crate) fn deactivate(&mut self) {
+        if let Some(engine) = self.active_engine_mut() {
+            engine.dismiss_han_nom_candidates();
+            engine.clear_predictions();
+        }
+        self.has_active = false;
+    }
+
+    /// Forgets the session for `context_id`, e.g. when its field closes.
+    pub(crate) fn drop_session(&mut self, context_id: &str) -> bool {
+        let Some(index) = self
+            .sessions
+            .iter()
+            .position(|session| session.context_id == context_id)
+        else {
+            return false;
+        };
+        if index + 1 == self.sessions.len() {
+            self.has_active = false;
+        }
+        self.sessions.remove(index);
+        true
+    }
+
+    pub(crate) fn drop_all_sessions(&mut self) {
+        self.sessions.clear();
+        self.has_active = false;
+    }
+
+    /// The engine of the active session, if one is active.
+    pub(crate) fn active_engine_mut(&mut self) -> Option<&mut VitypeEngine> {
+        if !self.has_active {
+            return None;
+        }
+        self.sessions
+            .last_mut()
+            .map(|session| session.engine.as_mut())
+    }
+
+    /// Copies the configuration into `session` unless it already has this generation.
+    fn refresh_configuration(session: &mut Session, configuration: &VitypeEngine, generation: u64) {
+        if session.configuration_generation == Some(generation) {
+            return;
+        }
+        session.engine.copy_configuration_from(configuration);
+        session.configuration_generation = Some(generation);
+    }
+
+    fn evict_to_limit(&mut self) {
+        let excess = self.sessions.len().saturating_sub(self.max_sessions);
+        self.sessions.drain(..excess);
+    }
+}
+
+impl VitypeEngine {
+    /// Takes over every setting of `other` but none of its typing state.
+    pub(super) fn copy_configuration_from(&mut self, other: &VitypeEngine) {
+        self.auto_fix_tone = other.auto_fix_tone;
+        self.free_tone_placement = other.free_tone_placement;
+        self.mixed_case_is_foreign = other.mixed_case_is_foreign;
+        self.smart_numeric_context = other.smart_numeric_context;
+        self.smart_backspace = other.smart_backspace;
+        self.tone_placement = other.tone_placement;
+        self.i_y_style = other.i_y_style;
+        self.orthography_packs = other.orthography_packs.clone();
+        self.loanwords = other.loanwords.clone();
+        self.literal_context_options = other.literal_context_options;
+        self.output_encoding = other.output_encoding;
+        self.input_method = other.input_method;
+        if self.history_word_limit != other.history_word_limit {
+            self.set_history_word_limit(other.history_word_limit);
+        }
+        self.copy_han_nom_settings_from(other);
+        self.copy_prediction_model_from(other);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::common::KeyTransformAction;
+use crate::VitypeEngine;
+
+// ==================== Smart Backspace ====================
+//
+// With smart backspace on, backspace first peels the marks off the word, newest
+// keystroke first: "vieejt" shows "việt", then "viêt", then "viet", and only then
+// loses letters. A mark key is one that shaped a letter typed by an earlier key,
+// which provenance records as any key after the first in a char's list.
+
+impl VitypeEngine {
+    pub(crate) fn set_smart_backspace(&mut self, enabled: bool) {
+        self.smart_backspace = enabled;
+    }
+
+    /// Raw indices of the keys that shaped an existing letter, newest first.
+    fn mark_keys(&self) -> Vec<usize> {
+        let mut keys: Vec<usize> = self
+            .provenance
+            .iter()
+            .flat_map(|keys| keys.iter().skip(1).copied())
+            .collect();
+        keys.sort_unstable_by(|a, b| b.cmp(a));
+        keys.dedup();
+        keys
+    }
+
+    /// Replays the word without its newest mark key. Only a replay that changes
+    /// nothing but the chars the key shaped counts; otherwise the next older mark
+    /// is tried. Returns the key's raw index, the replayed word and the first
+    /// changed char.
+    pub(super) fn word_without_last_mark(&self) -> Option<(usize, VitypeEngine, usize)> {
+        if self.buffer.is_empty() || self.is_foreign_mode {
+            return None;
+        }
+        for key in self.mark_keys() {
+            let raw: Vec<char> = self
+                .raw_buffer
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != key)
+                .map(|(_, ch)| *ch)
+                .collect();
+            let rebuilt = self.rebuild_current_word_from_raw(&raw);
+            if rebuilt.buffer.len() != self.buffer.len() || rebuilt.is_foreign_mode {
+                continue;
+            }
+            let only_shaped = self
+                .buffer
+                .iter()
+                .zip(&rebuilt.buffer)
+                .zip(&self.provenance)
+                .all(|((old, new), keys)| old == new || keys.contains(&key));
+            let start = self
+                .buffer
+                .iter()
+                .zip(&rebuilt.buffer)
+                .position(|(old, new)| old != new);
+            if let (true, Some(start)) = (only_shaped, start) {
+                return Some((key, rebuilt, start));
+            }
+        }
+        None
+    }
+
+    /// Removes the newest mark and returns the action that redraws the changed tail.
+    pub(super) fn remove_last_mark(&mut self) -> Option<KeyTransformAction> {
+        let (_, rebuilt, start) = self.word_without_last_mark()?;
+        let delete_count = self.buffer.len() - start;
+        self.adopt_current_word_state_from(rebuilt);
+        Some(KeyTransformAction {
+            delete_count,
+            text: self.buffer_string_from(start),
+        })
+    }
+}
@@ -0,0 +1,269 @@
+use crate::common::{lower_char, TonePlacement};
+use crate::diacritics::{apply_shape, escape_shape_preserving_tone, VowelShape};
+use crate::syllable::{
+    compose_syllable, is_vietnamese_letter, match_case, word_tokens, SyllableError, SyllableParts,
+    CODAS, ONSETS, TONE_KEYS,
+};
+use crate::VitypeEngine;
+
+const MAX_SUGGESTIONS: usize = 5;
+const MAX_EDIT_COST: usize = 6;
+
+// ==================== Issue ====================
+
+/// An invalid syllable found in a text, with ranked corrections (best first).
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Issue {
+    pub(crate) byte_start: usize,
+    pub(crate) byte_end: usize,
+    pub(crate) char_start: usize,
+    pub(crate) char_end: usize,
+    pub(crate) kind: SyllableError,
+    pub(crate) suggestions: Vec<String>,
+}
+
+// ==================== Helper Functions ====================
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Onsets that spell the same sound, chosen by the following vowel (c/k, g/gh, ng/ngh).
+fn is_onset_spelling_variant(a: &str, b: &str) -> bool {
+    matches!(
+        (a, b),
+        ("c", "k") | ("k", "c") | ("g", "gh") | ("gh", "g") | ("ng", "ngh") | ("ngh", "ng")
+    )
+}
+
+/// All letters sharing `ch`'s base vowel (a/ă/â, e/ê, o/ô/ơ, u/ư), plus the i/y pair.
+fn vowel_family(ch: char) -> Vec<char> {
+    let base = [VowelShape::Circumflex, VowelShape::Horn, VowelShape::Breve]
+        .iter()
+        .find_map(|shape| escape_shape_preserving_tone(ch, *shape))
+        .unwrap_or(ch);
+
+    let mut family = vec![base];
+    for shape in [VowelShape::Circumflex, VowelShape::Horn, VowelShape::Breve] {
+        if let Some(shaped) = apply_shape(base, shape) {
+            if !family.contains(&shaped) {
+                family.push(shaped);
+            }
+        }
+    }
+    match base {
+        'i' => family.push('y'),
+        'y' => family.push('i'),
+        _ => {}
+    }
+    family
+}
+
+/// Nucleus rewrites changing at most two letters, paired with the number of changes.
+fn nucleus_variants(nucleus: &[char]) -> Vec<(Vec<char>, usize)> {
+    let mut variants: Vec<(Vec<char>, usize)> = vec![(Vec::new(), 0)];
+    for ch in nucleus {
+        let mut next = Vec::new();
+        for (prefix, changes) in &variants {
+            for candidate in vowel_family(*ch) {
+                let changes = changes + usize::from(candidate != *ch);
+                if changes > 2 {
+                    continue;
+                }
+                let mut extended = prefix.clone();
+                extended.push(candidate);
+                next.push((extended, changes));
+            }
+        }
+        variants = next;
+    }
+    variants
+}
+
+/// Each edit costs 3, or 2 when it touches the part the error was reported on.
+/// Spelling-rule onset swaps and stop-final tone fixes cost 1, so they rank first.
+fn suggest_corrections(
+    parts: &SyllableParts,
+    kind: SyllableError,
+    tone_placement: TonePlacement,
+) -> Vec<(usize, String)> {
+    let unit = |matches_kind: bool| if matches_kind { 2 } else { 3 };
+    let onset = parts.onset_lower();
+    let coda = parts.coda_lower();
+    let nucleus: Vec<char> = parts.nucleus.iter().map(|ch| lower_char(*ch)).collect();
+
+    let onsets: Vec<(&str, usize)> = ONSETS
+        .iter()
+        .map(|candidate| (*candidate, edit_distance(candidate, &onset)))
+        .filter(|(_, distance)| *distance <= 1)
+        .map(|(candidate, distance)| {
+            if is_onset_spelling_variant(candidate, &onset) {
+                (candidate, 1)
+            } else {
+                (
+                    candidate,
+                    distance * unit(kind == SyllableError::InvalidOnset),
+                )
+            }
+        })
+        .collect();
+    let codas: Vec<(&str, usize)> = CODAS
+        .iter()
+        .map(|candidate| (*candidate, edit_distance(candidate, &coda)))
+        .filter(|(_, distance)| *distance <= 2)
+        .map(|(candidate, distance)| {
+            (
+                candidate,
+                distance * unit(kind == SyllableError::InvalidCoda),
+            )
+        })
+        .collect();
+    let nuclei: Vec<(Vec<char>, usize)> = nucleus_variants(&nucleus)
+        .into_iter()
+        .map(|(candidate, changes)| {
+            (
+                candidate,
+                changes * unit(kind == SyllableError::InvalidCluster),
+            )
+        })
+        .collect();
+    let tone_cost = |tone: Option<char>| {
+        if tone == parts.tone {
+            0
+        } else if kind == SyllableError::StopFinalTone && matches!(tone, Some('s' | 'j')) {
+            1
+        } else {
+            unit(false)
+        }
+    };
+
+    // A tone never makes an invalid toneless syllable valid, so each combination
+    // is checked once without a tone and only valid ones are tried with tones.
+    let mut ranked: Vec<(usize, String)> = Vec::new();
+    for (onset_candidate, onset_cost) in &onsets {
+        for (nucleus_candidate, nucleus_cost) in &nuclei {
+            if onset_cost + nucleus_cost > MAX_EDIT_COST {
+                continue;
+            }
+            for (coda_candidate, coda_cost) in &codas {
+                let base_cost = onset_cost + nucleus_cost + coda_cost;
+                if base_cost > MAX_EDIT_COST {
+                    continue;
+                }
+                let Some(toneless) = compose_syllable(
+                    onset_candidate,
+                    nucleus_candidate,
+                    coda_candidate,
+                    None,
+                    tone_placement,
+                ) else {
+                    continue;
+                };
+                for tone in TONE_KEYS {
+                    let cost = base_cost + tone_cost(tone);
+                    if cost > MAX_EDIT_COST {
+                        continue;
+                    }
+                    let word = match tone {
+                        None => Some(toneless.clone()),
+                        Some(_) => compose_syllable(
+                            onset_candidate,
+                            nucleus_candidate,
+                            coda_candidate,
+                            tone,
+                            tone_placement,
+                        ),
+                    };
+                    let Some(word) = word else {
+                        continue;
+                    };
+                    if !ranked.iter().any(|(_, existing)| *existing == word) {
+                        ranked.push((cost, word));
+                    }
+                }
+            }
+        }
+    }
+
+    ranked
+}
+
+/// Ranks corrections for both readings of a "gi"-style onset: "ngiêng" is tried
+/// as onset "ngi" + "ê" and as onset "ng" + "iê".
+fn ranked_corrections(
+    parts: &SyllableParts,
+    kind: SyllableError,
+    tone_placement: TonePlacement,
+) -> Vec<String> {
+    let mut ranked = suggest_corrections(parts, kind, tone_placement);
+
+    if parts.onset.len() > 1 && parts.onset.last().map(|ch| lower_char(*ch)) == Some('i') {
+        let mut regrouped = parts.clone();
+        let glide = regrouped.onset.pop().unwrap_or('i');
+        regrouped.nucleus.insert(0, glide);
+        for (cost, word) in suggest_corrections(&regrouped, kind, tone_placement) {
+            if !ranked.iter().any(|(_, existing)| *existing == word) {
+                ranked.push((cost, word));
+            }
+        }
+    }
+
+    ranked.sort_by_key(|(cost, _)| *cost);
+    ranked
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, word)| word)
+        .collect()
+}
+
+// ==================== Spell Check Methods on VitypeEngine ====================
+
+impl VitypeEngine {
+    /// Reports invalid Vietnamese syllables in `text` using this engine's tone placement.
+    /// Tokens with letters outside the Vietnamese alphabet and tokens without vowels
+    /// (abbreviations) are skipped.
+    pub(crate) fn check_text(&self, text: &str) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        for token in word_tokens(text) {
+            if !token.chars.iter().all(|ch| is_vietnamese_letter(*ch)) {
+                continue;
+            }
+
+            let lower: Vec<char> = token.chars.iter().map(|ch| lower_char(*ch)).collect();
+            let analyzer = VitypeEngine::for_word(&lower, self.tone_placement);
+            let kind = match analyzer.validate_syllable() {
+                Ok(_) => continue,
+                Err(kind) => kind,
+            };
+
+            let suggestions = match analyzer.split_syllable() {
+                Some(parts) => ranked_corrections(&parts, kind, self.tone_placement)
+                    .iter()
+                    .map(|word| match_case(&token.chars, word))
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            issues.push(Issue {
+                byte_start: token.byte_start,
+                byte_end: token.byte_end,
+                char_start: token.char_start,
+                char_end: token.char_end,
+                kind,
+                suggestions,
+            });
+        }
+        issues
+    }
+}
@@ -0,0 +1,171 @@
+use crate::common::{lower_char, InputMethod};
+use crate::diacritics::split_vowel_and_tone;
+use crate::{is_word_boundary, HistorySegment, VitypeEngine, WordSegment};
+
+// ==================== Helper Functions ====================
+
+/// The keys that type the unmarked letter `base`: the letter itself plus any
+/// shape or stroke key, in the letter's case.
+fn letter_keys(base: char, input_method: InputMethod) -> Vec<char> {
+    let lower = lower_char(base);
+    let (letter, key) = match (lower, input_method) {
+        ('â', InputMethod::Telex) => ('a', 'a'),
+        ('ă', InputMethod::Telex) => ('a', 'w'),
+        ('ê', InputMethod::Telex) => ('e', 'e'),
+        ('ô', InputMethod::Telex) => ('o', 'o'),
+        ('ơ', InputMethod::Telex) => ('o', 'w'),
+        ('ư', InputMethod::Telex) => ('u', 'w'),
+        ('đ', InputMethod::Telex) => ('d', 'd'),
+        ('â', InputMethod::Vni) => ('a', '6'),
+        ('ă', InputMethod::Vni) => ('a', '8'),
+        ('ê', InputMethod::Vni) => ('e', '6'),
+        ('ô', InputMethod::Vni) => ('o', '6'),
+        ('ơ', InputMethod::Vni) => ('o', '7'),
+        ('ư', InputMethod::Vni) => ('u', '7'),
+        ('đ', InputMethod::Vni) => ('d', '9'),
+        _ => return vec![base],
+    };
+    if base.is_uppercase() {
+        vec![letter.to_ascii_uppercase(), key.to_ascii_uppercase()]
+    } else {
+        vec![letter, key]
+    }
+}
+
+fn tone_key(tone: char, input_method: InputMethod) -> char {
+    match input_method {
+        InputMethod::Telex => tone,
+        InputMethod::Vni => match tone {
+            's' => '1',
+            'f' => '2',
+            'r' => '3',
+            'x' => '4',
+            _ => '5',
+        },
+    }
+}
+
+/// Keystrokes that could have produced `word`, with the tone key either at the
+/// end of the word or right after its vowel; both orders are worth replaying.
+fn candidate_keystrokes(word: &[char], input_method: InputMethod) -> Vec<Vec<char>> {
+    let mut keys: Vec<char> = Vec::new();
+    let mut tone: Option<(char, usize)> = None;
+    for ch in word {
+        let (base, mark) = split_vowel_and_tone(*ch);
+        keys.extend(letter_keys(base, input_method));
+        if let Some(mark) = mark {
+            let mut key = tone_key(mark, input_method);
+            if word.iter().all(|ch| !ch.is_lowercase()) {
+                key = key.to_ascii_uppercase();
+            }
+            tone = Some((key, keys.len()));
+        }
+    }
+    let Some((key, after_vowel)) = tone else {
+        return vec![keys];
+    };
+    let mut at_end = keys.clone();
+    at_end.push(key);
+    let mut after = keys;
+    after.insert(after_vowel, key);
+    vec![at_end, after]
+}
+
+// ==================== Surrounding Text Methods on VitypeEngine ====================
+
+impl VitypeEngine {
+    /// Rebuilds a word from its text, keeping the keystrokes that replay to it.
+    /// Text no keystrokes reproduce is kept as typed and locked.
+    fn infer_word_state(&self, word: &[char]) -> VitypeEngine {
+        for keys in candidate_keystrokes(word, self.input_method) {
+            let rebuilt = self.rebuild_current_word_from_raw(&keys);
+            if rebuilt.buffer == word {
+                return rebuilt;
+            }
+        }
+        let mut locked = VitypeEngine::new();
+        locked.buffer = word.to_vec();
+        locked.raw_buffer = word.to_vec();
+        locked.transforms_locked = true;
+        locked.reset_provenance();
+        locked
+    }
+
+    /// Whether the engine's words and boundaries are exactly what the host shows
+    /// before the caret, starting at a word boundary.
+    fn matches_text_before_cursor(&self, text: &[char]) -> bool {
+        if self.is_caret_inside_word() {
+            return false;
+        }
+        let visible = self.visible_text();
+        if visible.is_empty() || !text.ends_with(&visible) {
+            return visible.is_empty() && text.is_empty();
+        }
+        let start = text.len() - visible.len();
+        start == 0
+            || is_word_boundary(text[start - 1], self.input_method)
+            || is_word_boundary(visible[0], self.input_method)
+    }
+
+    /// Checks the engine's view against the text before the host's caret and, on a
+    /// mismatch, rebuilds history and the current word from that text. Returns
+    /// whether anything was rebuilt.
+    pub(crate) fn sync_surrounding_text(&mut self, text_before_cursor: &str) -> bool {
+        let text: Vec<char> = text_before_cursor.chars().collect();
+        if self.matches_text_before_cursor(&text) {
+            return false;
+        }
+        self.reset();
+
+        // Split the last line into alternating word and boundary runs.
+        let line_start = text
+            .iter()
+            .rposition(|ch| *ch == '\n' || *ch == '\r')
+            .map_or(0, |index| index + 1);
+        let mut runs: Vec<(bool, Vec<char>)> = Vec::new();
+        for ch in &text[line_start..] {
+            let is_word = !is_word_boundary(*ch, self.input_method);
+            match runs.last_mut() {
+                Some((run_is_word, chars)) if *run_is_word == is_word => chars.push(*ch),
+                _ => runs.push((is_word, vec![*ch])),
+            }
+        }
+
+        let current = match runs.last() {
+            Some((true, _)) => runs.pop().map(|(_, chars)| chars),
+            _ => None,
+        };
+        let mut words_kept = 0;
+        let first_kept = runs
+            .iter()
+            .rposition(|(is_word, _)| {
+                if *is_word {
+                    words_kept += 1;
+                }
+                words_kept > self.history_word_limit
+            })
+            .map_or(0, |index| index + 1);
+        for (is_word, chars) in runs.drain(first_kept..) {
+            if !is_word {
+                self.history.push_back(HistorySegment::Boundary(chars));
+                continue;
+            }
+            let state = self.infer_word_state(&chars);
+            self.history.push_back(HistorySegment::Word(WordSegment {
+                buffer: state.buffer,
+                raw_buffer: state.raw_buffer,
+                provenance: state.provenance,
+                is_foreign_mode: state.is_foreign_mode,
+                transforms_locked: state.transforms_locked,
+            }));
+        }
+        self.trim_history_to_word_limit();
+
+        if let Some(word) = current {
+            let state = self.infer_word_state(&word);
+            self.adopt_current_word_state_from(state);
+        }
+        self.refresh_literal_context();
+        true
+    }
+}
@@ -0,0 +1,352 @@
+use crate::common::{is_vowel, lower_char, TonePlacement, BASE_VOWELS, TONED_TO_BASE};
+use crate::diacritics::{apply_tone, split_vowel_and_tone};
+use crate::VitypeEngine;
+
+// ==================== Syllable Tables ====================
+
+pub(crate) const ONSETS: [&str; 28] = [
+    "", "b", "c", "ch", "d", "đ", "g", "gh", "gi", "h", "k", "kh", "l", "m", "n", "ng", "ngh",
+    "nh", "p", "ph", "qu", "r", "s", "t", "th", "tr", "v", "x",
+];
+
+pub(crate) const CODAS: [&str; 10] = ["", "c", "ch", "k", "m", "n", "ng", "nh", "p", "t"];
+
+/// No tone first, then the Telex tone keys.
+pub(crate) const TONE_KEYS: [Option<char>; 6] =
+    [None, Some('s'), Some('f'), Some('r'), Some('x'), Some('j')];
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum CodaRule {
+    Optional,
+    Required,
+    Forbidden,
+}
+
+/// Shaped nuclei (glide + main vowel + off-glide) and whether they take a final consonant.
+/// Every entry also passes `is_valid_vowel_cluster_chars` once the shapes are stripped.
+pub(crate) const NUCLEI: [(&str, CodaRule); 50] = [
+    ("a", CodaRule::Optional),
+    ("ă", CodaRule::Required),
+    ("â", CodaRule::Required),
+    ("e", CodaRule::Optional),
+    ("ê", CodaRule::Optional),
+    ("i", CodaRule::Optional),
+    ("o", CodaRule::Optional),
+    ("ô", CodaRule::Optional),
+    ("ơ", CodaRule::Optional),
+    ("u", CodaRule::Optional),
+    ("ư", CodaRule::Optional),
+    ("y", CodaRule::Optional),
+    ("ai", CodaRule::Forbidden),
+    ("ao", CodaRule::Forbidden),
+    ("au", CodaRule::Forbidden),
+    ("ay", CodaRule::Forbidden),
+    ("âu", CodaRule::Forbidden),
+    ("ây", CodaRule::Forbidden),
+    ("eo", CodaRule::Forbidden),
+    ("êu", CodaRule::Forbidden),
+    ("ia", CodaRule::Forbidden),
+    ("iê", CodaRule::Required),
+    ("iu", CodaRule::Forbidden),
+    ("oa", CodaRule::Optional),
+    ("oă", CodaRule::Required),
+    ("oe", CodaRule::Optional),
+    ("oi", CodaRule::Forbidden),
+    ("ôi", CodaRule::Forbidden),
+    ("ơi", CodaRule::Forbidden),
+    ("ua", CodaRule::Forbidden),
+    ("uâ", CodaRule::Required),
+    ("uê", CodaRule::Optional),
+    ("ui", CodaRule::Forbidden),
+    ("uô", CodaRule::Required),
+    ("uơ", CodaRule::Forbidden),
+    ("uy", CodaRule::Optional),
+    ("ưa", CodaRule::Forbidden),
+    ("ưi", CodaRule::Forbidden),
+    ("ưu", CodaRule::Forbidden),
+    ("ươ", CodaRule::Required),
+    ("yê", CodaRule::Required),
+    ("iêu", CodaRule::Forbidden),
+    ("yêu", CodaRule::Forbidden),
+    ("oai", CodaRule::Forbidden),
+    ("oay", CodaRule::Forbidden),
+    ("uôi", CodaRule::Forbidden),
+    ("ươi", CodaRule::Forbidden),
+    ("ươu", CodaRule::Forbidden),
+    ("uya", CodaRule::Forbidden),
+    ("uyê", CodaRule::Required),
+];
+
+pub(crate) fn nucleus_coda_rule(nucleus: &str) -> Option<CodaRule> {
+    NUCLEI
+        .iter()
+        .find(|(entry, _)| *entry == nucleus)
+        .map(|(_, rule)| *rule)
+}
+
+pub(crate) fn is_vietnamese_letter(ch: char) -> bool {
+    let lower = lower_char(ch);
+    if lower.is_ascii_lowercase() {
+        return !matches!(lower, 'f' | 'j' | 'w' | 'z');
+    }
+    lower == 'đ' || BASE_VOWELS.contains(&ch) || TONED_TO_BASE.contains_key(&ch)
+}
+
+pub(crate) fn is_stop_coda(coda: &str) -> bool {
+    matches!(coda, "p" | "t" | "c" | "ch" | "k")
+}
+
+fn is_front_vowel(ch: char) -> bool {
+    matches!(ch, 'i' | 'e' | 'ê' | 'y')
+}
+
+fn is_valid_onset_before(onset: &str, first_vowel: char) -> bool {
+    if !ONSETS.contains(&onset) {
+        return false;
+    }
+    match onset {
+        "k" | "gh" | "ngh" => is_front_vowel(first_vowel),
+        "c" | "ng" => !is_front_vowel(first_vowel),
+        // "gì", "gìn" spell the /z/ onset with a single g before i.
+        "g" => !is_front_vowel(first_vowel) || first_vowel == 'i',
+        _ => true,
+    }
+}
+
+// ==================== Syllable Parts ====================
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum SyllableError {
+    InvalidCluster,
+    InvalidOnset,
+    InvalidCoda,
+    StopFinalTone,
+    MisplacedTone,
+}
+
+/// A word split into onset, nucleus and coda. `nucleus` keeps shapes but not tones;
+/// the tone (as an internal Telex key) and the index of the marked letter are kept apart.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SyllableParts {
+    pub(crate) onset: Vec<char>,
+    pub(crate) nucleus: Vec<char>,
+    pub(crate) coda: Vec<char>,
+    pub(crate) nucleus_start: usize,
+    pub(crate) tone: Option<char>,
+    pub(crate) toned_index: Option<usize>,
+    pub(crate) tone_marks: usize,
+}
+
+impl SyllableParts {
+    pub(crate) fn onset_lower(&self) -> String {
+        self.onset.iter().map(|ch| lower_char(*ch)).collect()
+    }
+
+    pub(crate) fn nucleus_lower(&self) -> String {
+        self.nucleus.iter().map(|ch| lower_char(*ch)).collect()
+    }
+
+    pub(crate) fn coda_lower(&self) -> String {
+        self.coda.iter().map(|ch| lower_char(*ch)).collect()
+    }
+}
+
+/// Joins the parts, places `tone` where the engine would, and returns the word only
+/// when it is a valid syllable.
+pub(crate) fn compose_syllable(
+    onset: &str,
+    nucleus: &[char],
+    coda: &str,
+    tone: Option<char>,
+    tone_placement: TonePlacement,
+) -> Option<String> {
+    let mut chars: Vec<char> = onset.chars().collect();
+    chars.extend_from_slice(nucleus);
+    chars.extend(coda.chars());
+
+    let mut engine = VitypeEngine::for_word(&chars, tone_placement);
+    if let Some(tone_key) = tone {
+        let target = engine.tone_target_index()?;
+        chars[target] = apply_tone(chars[target], Some(tone_key))?;
+        engine = VitypeEngine::for_word(&chars, tone_placement);
+    }
+
+    match engine.validate_syllable() {
+        Ok(Some(_)) => Some(chars.into_iter().collect()),
+        _ => None,
+    }
+}
+
+// ==================== Syllable Methods on VitypeEngine ====================
+
+impl VitypeEngine {
+    /// Builds a scratch engine whose current word is `word`, so the engine's own
+    /// cluster and tone rules can be evaluated against already-written text.
+    pub(crate) fn for_word(word: &[char], tone_placement: TonePlacement) -> VitypeEngine {
+        let mut engine = VitypeEngine::new();
+        engine.tone_placement = tone_placement;
+        engine.buffer = word.to_vec();
+        engine.raw_buffer = word.to_vec();
+        engine
+    }
+
+    pub(crate) fn tone_target_index(&self) -> Option<usize> {
+        self.find_target_vowel_index(self.buffer.len())
+    }
+
+    /// Splits the current buffer using the same vowel scan as tone placement,
+    /// so "qu" and "gi" end up in the onset. Returns `None` without a vowel or
+    /// when the vowels do not form a single cluster.
+    pub(crate) fn split_syllable(&self) -> Option<SyllableParts> {
+        let mut vowel_indices: Vec<usize> = Vec::new();
+        self.for_each_effective_vowel_index(self.buffer.len(), |index| {
+            vowel_indices.push(index);
+            true
+        });
+
+        let first = *vowel_indices.first()?;
+        let last = *vowel_indices.last()?;
+        if last + 1 - first != vowel_indices.len() {
+            return None;
+        }
+        if self.buffer[last + 1..].iter().any(|ch| is_vowel(*ch)) {
+            return None;
+        }
+
+        let mut tone: Option<char> = None;
+        let mut toned_index: Option<usize> = None;
+        let mut tone_marks = 0usize;
+        for (index, ch) in self.buffer.iter().enumerate() {
+            if let (_, Some(tone_key)) = split_vowel_and_tone(*ch) {
+                tone = Some(tone_key);
+                toned_index = Some(index);
+                tone_marks += 1;
+            }
+        }
+
+        Some(SyllableParts {
+            onset: self.buffer[..first].to_vec(),
+            nucleus: self.buffer[first..=last]
+                .iter()
+                .map(|ch| split_vowel_and_tone(*ch).0)
+                .collect(),
+            coda: self.buffer[last + 1..].to_vec(),
+            nucleus_start: first,
+            tone,
+            toned_index,
+            tone_marks,
+        })
+    }
+
+    /// Validates the current buffer as a complete written syllable (onset, cluster,
+    /// final consonant and tone position). Returns `Ok(None)` for words without a vowel.
+    pub(crate) fn validate_syllable(&self) -> Result<Option<SyllableParts>, SyllableError> {
+        let len = self.buffer.len();
+        if self.last_effective_vowel_index(len).is_none() {
+            return Ok(None);
+        }
+        if self.has_multiple_vowel_clusters(len) || !self.is_valid_tone_cluster(len) {
+            return Err(SyllableError::InvalidCluster);
+        }
+        let parts = self.split_syllable().ok_or(SyllableError::InvalidCluster)?;
+
+        let nucleus = parts.nucleus_lower();
+        let coda = parts.coda_lower();
+        let coda_rule = nucleus_coda_rule(&nucleus).ok_or(SyllableError::InvalidCluster)?;
+
+        let first_vowel = nucleus.chars().next().unwrap_or('\0');
+        if !is_valid_onset_before(&parts.onset_lower(), first_vowel) {
+            return Err(SyllableError::InvalidOnset);
+        }
+
+        if self.has_invalid_final_consonant(len)
+            || (!coda.is_empty() && !CODAS.contains(&coda.as_str()))
+        {
+            return Err(SyllableError::InvalidCoda);
+        }
+        match coda_rule {
+            CodaRule::Required if coda.is_empty() => return Err(SyllableError::InvalidCoda),
+            CodaRule::Forbidden if !coda.is_empty() => return Err(SyllableError::InvalidCoda),
+            _ => {}
+        }
+        if matches!(coda.as_str(), "ch" | "nh")
+            && !matches!(nucleus.chars().last(), Some('a' | 'ê' | 'i' | 'y'))
+        {
+            return Err(SyllableError::InvalidCoda);
+        }
+
+        if parts.tone_marks > 1 {
+            return Err(SyllableError::MisplacedTone);
+        }
+        if let Some(tone) = parts.tone {
+            if is_stop_coda(&coda) && !matches!(tone, 's' | 'j') {
+                return Err(SyllableError::StopFinalTone);
+            }
+            if parts.toned_index != self.tone_target_index() {
+                return Err(SyllableError::MisplacedTone);
+            }
+        }
+
+        Ok(Some(parts))
+    }
+}
+
+// ==================== Text Tokens ====================
+
+/// A maximal run of alphabetic characters inside a larger text, with both byte
+/// and char offsets so hosts can map it back to their own string indices.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct WordToken {
+    pub(crate) byte_start: usize,
+    pub(crate) byte_end: usize,
+    pub(crate) char_start: usize,
+    pub(crate) char_end: usize,
+    pub(crate) chars: Vec<char>,
+}
+
+pub(crate) fn word_tokens(text: &str) -> Vec<WordToken> {
+    tokens_matching(text, char::is_alphabetic)
+}
+
+/// Splits `text` into maximal runs of characters accepted by `is_token_char`.
+pub(crate) fn tokens_matching(text: &str, is_token_char: impl Fn(char) -> bool) -> Vec<WordToken> {
+    let mut tokens: Vec<WordToken> = Vec::new();
+    let mut current: Option<WordToken> = None;
+
+    for (char_index, (byte_index, ch)) in text.char_indices().enumerate() {
+        if is_token_char(ch) {
+            let token = current.get_or_insert_with(|| WordToken {
+                byte_start: byte_index,
+                byte_end: byte_index,
+                char_start: char_index,
+                char_end: char_index,
+                chars: Vec::new(),
+            });
+            token.chars.push(ch);
+            token.byte_end = byte_index + ch.len_utf8();
+            token.char_end = char_index + 1;
+        } else if let Some(token) = current.take() {
+            tokens.push(token);
+        }
+    }
+
+    if let Some(token) = current {
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Re-applies the capitalisation of `template` (all caps or leading capital) to `word`.
+pub(crate) fn match_case(template: &[char], word: &str) -> String {
+    let is_all_caps = template.len() > 1 && template.iter().all(|ch| !ch.is_lowercase());
+    if is_all_caps {
+        return word.chars().flat_map(|ch| ch.to_uppercase()).collect();
+    }
+    if template.first().is_some_and(|ch| ch.is_uppercase()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            return first.to_uppercase().chain(chars).collect();
+        }
+    }
+    word.to_string()
+}
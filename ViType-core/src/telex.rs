@@ -7,7 +7,7 @@ use crate::VitypeEngine;
 
 // ==================== Telex Helper Functions ====================
 
-fn is_tone_key(ch: char) -> bool {
+pub(super) fn is_tone_key(ch: char) -> bool {
     matches!(lower_char(ch), 's' | 'f' | 'r' | 'x' | 'j' | 'z')
 }
 
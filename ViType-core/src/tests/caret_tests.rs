@@ -0,0 +1,118 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::Host;
+use crate::{InputMethod, VitypeEngine};
+
+#[test]
+fn testInsertShapeKeyInsideWord() {
+    let mut host = Host::new(VitypeEngine::new());
+    host.type_at_caret("tieng").move_by(-3).type_at_caret("e");
+    assert_eq!(host.show(), "tiê|ng");
+    host.move_by(10).type_at_caret("s");
+    assert_eq!(host.show(), "tiếng|");
+}
+
+#[test]
+fn testInsertToneKeyInsideWord() {
+    let mut host = Host::new(VitypeEngine::new());
+    host.type_at_caret("tieeng").move_by(-2).type_at_caret("s");
+    assert_eq!(host.show(), "tiế|ng");
+    host.type_at_caret("x");
+    assert_eq!(host.show(), "tiễ|ng");
+}
+
+#[test]
+fn testInsertLetterInsideWordReplaysTone() {
+    let mut host = Host::new(VitypeEngine::new());
+    host.type_at_caret("hoaf").move_by(-3).type_at_caret("t");
+    assert_eq!(host.show(), "t|hòa");
+}
+
+#[test]
+fn testDeleteForwardInsideWord() {
+    let mut host = Host::new(VitypeEngine::new());
+    host.type_at_caret("hoaf").move_by(-3).delete_forward();
+    assert_eq!(host.show(), "|òa");
+}
+
+#[test]
+fn testDeleteBackwardRemovesWholeKeystrokeGroup() {
+    let mut host = Host::new(VitypeEngine::new());
+    host.type_at_caret("ddaay").move_by(-2).delete_backward();
+    assert_eq!(host.show(), "|ây");
+}
+
+#[test]
+fn testBoundaryInsideWordSplitsIt() {
+    let mut host = Host::new(VitypeEngine::new());
+    host.type_at_caret("hoa").move_by(-1).type_at_caret(" ");
+    assert_eq!(host.show(), "ho |a");
+    host.type_at_caret("s");
+    assert_eq!(host.show(), "ho s|a");
+    host.move_by(1).type_at_caret("s");
+    assert_eq!(host.show(), "ho sá|");
+}
+
+#[test]
+fn testDeletingBoundaryJoinsWords() {
+    let mut host = Host::new(VitypeEngine::new());
+    host.type_at_caret("to an").move_by(-3).delete_forward();
+    assert_eq!(host.show(), "to|an");
+    host.move_by(2).type_at_caret("s");
+    assert_eq!(host.show(), "toán|");
+
+    let mut host = Host::new(VitypeEngine::new());
+    host.type_at_caret("to an").move_by(-2).delete_backward();
+    assert_eq!(host.show(), "to|an");
+}
+
+#[test]
+fn testNavigatesBeyondDefaultHistory() {
+    let mut host = Host::new(VitypeEngine::new());
+    host.engine.set_history_word_limit(8);
+    host.type_at_caret("mot hai ba bon nam");
+    host.move_by(-16);
+    assert_eq!(host.show(), "mo|t hai ba bon nam");
+    host.type_at_caret("o").type_at_caret("j");
+    assert_eq!(host.show(), "mộ|t hai ba bon nam");
+    host.move_by(100).type_at_caret("w");
+    assert_eq!(host.show(), "một hai ba bon năm|");
+}
+
+#[test]
+fn testMoveStopsAtKnownText() {
+    let mut host = Host::new(VitypeEngine::new());
+    host.type_at_caret("ab");
+    assert_eq!(host.engine.move_cursor(-5), -2);
+    assert_eq!(host.engine.move_cursor(5), 2);
+}
+
+#[test]
+fn testProcessInsideWordResets() {
+    let mut engine = VitypeEngine::new();
+    for ch in "hoa".chars() {
+        engine.process(&ch.to_string());
+    }
+    engine.move_cursor(-1);
+    assert_eq!(engine.process("s"), None);
+    assert!(engine.history.is_empty());
+    assert_eq!(engine.buffer, vec!['s']);
+}
+
+#[test]
+fn testInsertReplaysUnderNumericContext() {
+    let mut host = Host::new(VitypeEngine::new());
+    host.engine.input_method = InputMethod::Vni;
+    host.engine.set_smart_numeric_context(true);
+    host.type_at_caret("3a1").move_by(-1);
+    host.type_at_caret("2");
+    assert_eq!(host.show(), "3a2|1");
+
+    let mut host = Host::new(VitypeEngine::new());
+    host.engine.input_method = InputMethod::Vni;
+    host.engine.set_smart_numeric_context(true);
+    host.type_at_caret("ba6175").move_by(-2);
+    assert_eq!(host.show(), "bấ|75");
+    host.type_at_caret("2");
+    assert_eq!(host.show(), "bầ|75");
+}
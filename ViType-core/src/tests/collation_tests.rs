@@ -0,0 +1,117 @@
+#![allow(non_snake_case)]
+
+use std::cmp::Ordering;
+
+use crate::collation::{compare, sort_key, CaseOrder, CollationOptions};
+
+fn sorted(words: &[&str], options: CollationOptions) -> Vec<String> {
+    let mut words: Vec<String> = words.iter().map(|word| word.to_string()).collect();
+    words.sort_by(|a, b| compare(a, b, options));
+    words
+}
+
+#[test]
+fn testAlphabetOrder() {
+    assert_eq!(
+        sorted(
+            &["ư", "u", "ơ", "ô", "o", "đ", "d", "ê", "e", "â", "ă", "a", "b"],
+            CollationOptions::default()
+        ),
+        vec!["a", "ă", "â", "b", "d", "đ", "e", "ê", "o", "ô", "ơ", "u", "ư"]
+    );
+}
+
+#[test]
+fn testLettersBeforeTones() {
+    // Tone only breaks ties: "an" < "ăn" even though "án" carries a mark.
+    assert_eq!(
+        sorted(&["ăn", "án", "an"], CollationOptions::default()),
+        vec!["an", "án", "ăn"]
+    );
+    assert_eq!(
+        sorted(
+            &["mạ", "má", "mã", "mả", "mà", "ma"],
+            CollationOptions::default()
+        ),
+        vec!["ma", "mà", "mả", "mã", "má", "mạ"]
+    );
+}
+
+#[test]
+fn testNamesAndSpaces() {
+    assert_eq!(
+        sorted(
+            &["Đặng Văn Lâm", "Anh", "An Bình", "Dương"],
+            CollationOptions::default()
+        ),
+        vec!["An Bình", "Anh", "Dương", "Đặng Văn Lâm"]
+    );
+}
+
+#[test]
+fn testDigraphsAsUnits() {
+    let words = ["chi", "cu", "ngu", "nhà", "nghề", "nu", "tre", "thu", "tu"];
+    assert_eq!(
+        sorted(&words, CollationOptions::default()),
+        vec!["chi", "cu", "nghề", "ngu", "nhà", "nu", "thu", "tre", "tu"]
+    );
+    let options = CollationOptions {
+        digraphs_as_units: true,
+        ..CollationOptions::default()
+    };
+    assert_eq!(
+        sorted(&words, options),
+        vec!["cu", "chi", "nu", "ngu", "nghề", "nhà", "tu", "thu", "tre"]
+    );
+}
+
+#[test]
+fn testDigraphsAtEverySyllable() {
+    let options = CollationOptions {
+        digraphs_as_units: true,
+        ..CollationOptions::default()
+    };
+    assert_eq!(
+        sorted(&["cà phê", "cà pu", "cà-trớn", "cà-tu"], options),
+        vec!["cà pu", "cà phê", "cà-tu", "cà-trớn"]
+    );
+}
+
+#[test]
+fn testDecomposedTextSortsLikePrecomposed() {
+    let options = CollationOptions::default();
+    let decomposed = "Vie\u{302}\u{323}t Nam";
+    assert_eq!(sort_key(decomposed, options), sort_key("Việt Nam", options));
+    assert_eq!(
+        sorted(&["vu", "vie\u{302}\u{323}t", "vi"], options),
+        vec!["vi", "vie\u{302}\u{323}t", "vu"]
+    );
+}
+
+#[test]
+fn testCaseOrder() {
+    assert_eq!(
+        compare("an", "An", CollationOptions::default()),
+        Ordering::Equal
+    );
+    let lower_first = CollationOptions {
+        case_order: CaseOrder::LowerFirst,
+        ..CollationOptions::default()
+    };
+    assert_eq!(compare("an", "An", lower_first), Ordering::Less);
+    let upper_first = CollationOptions {
+        case_order: CaseOrder::UpperFirst,
+        ..CollationOptions::default()
+    };
+    assert_eq!(compare("an", "An", upper_first), Ordering::Greater);
+    // Case never outweighs letters or tones.
+    assert_eq!(compare("Ba", "bà", upper_first), Ordering::Less);
+}
+
+#[test]
+fn testSortKeyMatchesCompare() {
+    let options = CollationOptions::default();
+    assert!(sort_key("hoa", options) < sort_key("hòa", options));
+    assert!(sort_key("hòa", options) < sort_key("hoan", options));
+    assert!(sort_key("9x", options) < sort_key("an", options));
+}
@@ -0,0 +1,109 @@
+#![allow(non_snake_case)]
+
+use crate::confusable::ConfusionKind;
+use crate::lexicon::Lexicon;
+use crate::VitypeEngine;
+
+use super::test_helpers::apply_input;
+
+fn suggestions(text: &str) -> Vec<(String, String, ConfusionKind)> {
+    let engine = VitypeEngine::new();
+    engine
+        .check_confusables(text)
+        .into_iter()
+        .map(|confusion| {
+            (
+                text[confusion.byte_start..confusion.byte_end].to_string(),
+                confusion.suggestion,
+                confusion.kind,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn testHoiNgaUsesContext() {
+    assert_eq!(
+        suggestions("Tôi uống sửa mỗi sáng"),
+        vec![("sửa".to_string(), "sữa".to_string(), ConfusionKind::HoiNga)]
+    );
+    assert_eq!(
+        suggestions("Thợ sữa xe"),
+        vec![("sữa".to_string(), "sửa".to_string(), ConfusionKind::HoiNga)]
+    );
+    assert!(suggestions("Tôi sửa xe và uống sữa").is_empty());
+}
+
+#[test]
+fn testOnsetConfusions() {
+    assert_eq!(suggestions("chung tâm thành phố")[0].1, "trung".to_string());
+    assert_eq!(suggestions("da đình tôi")[0].2, ConfusionKind::DGiR);
+    assert_eq!(suggestions("da đình tôi")[0].1, "gia");
+}
+
+#[test]
+fn testFinalConsonantConfusions() {
+    let found = suggestions("người việc nam");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].1, "việt");
+    assert_eq!(found[0].2, ConfusionKind::FinalTC);
+}
+
+#[test]
+fn testCorrectTextIsNotFlagged() {
+    assert!(suggestions("xin chào các bạn").is_empty());
+    assert!(suggestions("Gia đình tôi có bốn người.").is_empty());
+}
+
+#[test]
+fn testCommonSentencesAreNotFlagged() {
+    for sentence in [
+        "Sân trường rất rộng",
+        "Hôm nay trời mưa to",
+        "Mẹ tôi đang nấu cơm trong bếp",
+        "Chúng tôi đi học bằng xe buýt",
+        "Cuối tuần này bạn có rảnh không?",
+        "Anh ấy làm việc ở một công ty lớn",
+        "Thành phố rất đông người vào buổi sáng",
+        "Con chó nhà tôi rất thông minh",
+    ] {
+        assert!(suggestions(sentence).is_empty(), "{sentence}");
+    }
+}
+
+#[test]
+fn testSuggestionKeepsCase() {
+    assert_eq!(suggestions("Chung tâm")[0].1, "Trung");
+}
+
+#[test]
+fn testCustomLexicon() {
+    let lexicon = Lexicon::parse("ngã\t5\nngã tư\t5\ntư\t5\nngả\t1\n");
+    let found =
+        crate::confusable::find_confusions("ngả tư", &lexicon, crate::TonePlacement::Orthographic);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].suggestion, "ngã");
+    assert!(found[0].confidence > 0.5);
+}
+
+#[test]
+fn testWordsMissingFromLexiconAreNotFlagged() {
+    let lexicon = Lexicon::parse("ngã\t5\nngã tư\t5\ntư\t5\n");
+    let found =
+        crate::confusable::find_confusions("ngả tư", &lexicon, crate::TonePlacement::Orthographic);
+    assert!(found.is_empty());
+}
+
+#[test]
+fn testHistoryWordsAreChecked() {
+    let mut engine = VitypeEngine::new();
+    for ch in "uoongs suwar ".chars() {
+        engine.process(&ch.to_string());
+    }
+    assert_eq!(apply_input("uoongs suwar "), "uống sửa ");
+    assert_eq!(engine.history_text(), "uống sửa ");
+    let found = engine.check_history_confusables();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].suggestion, "sữa");
+    assert_eq!(found[0].char_start, 5);
+}
@@ -0,0 +1,138 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::apply_ops;
+use crate::edit_script::{edit_script, trimmed_edit_script, EditOp};
+use crate::VitypeEngine;
+
+fn chars(text: &str) -> Vec<char> {
+    text.chars().collect()
+}
+
+fn type_with_edit_scripts(input: &str, trimmed: bool) -> String {
+    let mut engine = VitypeEngine::new();
+    let mut output: Vec<char> = Vec::new();
+    let mut caret = 0;
+    for ch in input.chars() {
+        match engine.process_edit_script(&ch.to_string(), trimmed) {
+            Some(ops) => apply_ops(&mut output, &mut caret, &ops),
+            None => {
+                output.insert(caret, ch);
+                caret += 1;
+            }
+        }
+        assert_eq!(caret, output.len());
+    }
+    output.into_iter().collect()
+}
+
+#[test]
+fn testToneChangeOnlyTouchesVowel() {
+    assert_eq!(
+        edit_script(&chars("nghiêng"), &chars("nghiếng")),
+        vec![
+            EditOp::MoveLeft(2),
+            EditOp::Delete(1),
+            EditOp::Insert("ế".to_string()),
+            EditOp::MoveRight(2),
+        ]
+    );
+}
+
+#[test]
+fn testAppendNeedsNoMoves() {
+    assert_eq!(
+        edit_script(&chars("vi"), &chars("viê")),
+        vec![EditOp::Insert("ê".to_string())]
+    );
+    assert_eq!(
+        edit_script(&chars("ab"), &chars("ab")),
+        Vec::<EditOp>::new()
+    );
+}
+
+#[test]
+fn testSeparateChangesAreEditedInPlace() {
+    let old = chars("huong");
+    let new = chars("hương");
+    let ops = edit_script(&old, &new);
+    assert_eq!(
+        ops,
+        vec![
+            EditOp::MoveLeft(2),
+            EditOp::Delete(2),
+            EditOp::Insert("ươ".to_string()),
+            EditOp::MoveRight(2),
+        ]
+    );
+
+    assert_eq!(
+        edit_script(&chars("abcde"), &chars("aXcYe")),
+        vec![
+            EditOp::MoveLeft(1),
+            EditOp::Delete(1),
+            EditOp::Insert("Y".to_string()),
+            EditOp::MoveLeft(2),
+            EditOp::Delete(1),
+            EditOp::Insert("X".to_string()),
+            EditOp::MoveRight(3),
+        ]
+    );
+
+    let old = chars("dden");
+    let new = chars("đèn");
+    let mut text = old.clone();
+    let mut caret = text.len();
+    apply_ops(&mut text, &mut caret, &edit_script(&old, &new));
+    assert_eq!(text, new);
+    assert_eq!(caret, new.len());
+}
+
+#[test]
+fn testTrimmedFormReplacesMiddle() {
+    assert_eq!(
+        trimmed_edit_script(&chars("hoaf"), &chars("hoà")),
+        vec![EditOp::Delete(2), EditOp::Insert("à".to_string())]
+    );
+    assert_eq!(
+        trimmed_edit_script(&chars("tuoi"), &chars("tươi")),
+        vec![
+            EditOp::MoveLeft(1),
+            EditOp::Delete(2),
+            EditOp::Insert("ươ".to_string()),
+            EditOp::MoveRight(1),
+        ]
+    );
+}
+
+#[test]
+fn testEngineScriptsReproduceOutput() {
+    for input in [
+        "nghieengs",
+        "tuwowngf",
+        "ddaay laf",
+        "vieetj nam",
+        "hoas@mail",
+        "balf",
+    ] {
+        let expected = super::test_helpers::apply_input(input);
+        assert_eq!(type_with_edit_scripts(input, false), expected, "{input}");
+        assert_eq!(type_with_edit_scripts(input, true), expected, "{input}");
+    }
+}
+
+#[test]
+fn testEngineToneScriptKeepsSuffix() {
+    let mut engine = VitypeEngine::new();
+    for ch in "nghieeng".chars() {
+        engine.process_edit_script(&ch.to_string(), false);
+    }
+    assert_eq!(
+        engine.process_edit_script("s", false),
+        Some(vec![
+            EditOp::MoveLeft(2),
+            EditOp::Delete(1),
+            EditOp::Insert("ế".to_string()),
+            EditOp::MoveRight(2),
+        ])
+    );
+}
@@ -0,0 +1,116 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::{apply, string, type_keys};
+use crate::VitypeEngine;
+
+#[test]
+fn testReplaysForeignWordAsVietnamese() {
+    let mut engine = VitypeEngine::new();
+    let mut text = Vec::new();
+    type_keys(&mut engine, &mut text, "Caaul");
+    assert_eq!(string(&text), "Caaul");
+
+    apply(&mut text, engine.force_vietnamese(false).unwrap());
+    assert_eq!(string(&text), "Câul");
+    assert!(!engine.is_foreign_mode);
+}
+
+#[test]
+fn testRestOfWordKeepsTransforming() {
+    let mut engine = VitypeEngine::new();
+    let mut text = Vec::new();
+    type_keys(&mut engine, &mut text, "eede");
+    assert_eq!(string(&text), "eede");
+    apply(&mut text, engine.force_vietnamese(false).unwrap());
+    assert_eq!(string(&text), "êde");
+    type_keys(&mut engine, &mut text, "e");
+    assert_eq!(string(&text), "êdê");
+}
+
+#[test]
+fn testOnlyThisWordByDefault() {
+    let mut engine = VitypeEngine::new();
+    let mut text = Vec::new();
+    type_keys(&mut engine, &mut text, "caaul");
+    apply(&mut text, engine.force_vietnamese(false).unwrap());
+    type_keys(&mut engine, &mut text, " caaul");
+    assert_eq!(string(&text), "câul caaul");
+}
+
+#[test]
+fn testRememberedForTheSession() {
+    let mut engine = VitypeEngine::new();
+    let mut text = Vec::new();
+    type_keys(&mut engine, &mut text, "caaul");
+    apply(&mut text, engine.force_vietnamese(true).unwrap());
+    engine.reset();
+    text.clear();
+    type_keys(&mut engine, &mut text, "Caaul");
+    assert_eq!(string(&text), "Câul");
+
+    engine.clear_forced_vietnamese_words();
+    type_keys(&mut engine, &mut text, " caaul");
+    assert_eq!(string(&text), "Câul caaul");
+}
+
+#[test]
+fn testVietnameseWordIsUnchanged() {
+    let mut engine = VitypeEngine::new();
+    let mut text = Vec::new();
+    type_keys(&mut engine, &mut text, "vieejt");
+    assert!(engine.force_vietnamese(false).is_none());
+    assert!(VitypeEngine::new().force_vietnamese(false).is_none());
+}
+
+#[test]
+fn testTypingWAfterForcingKeepsProvenance() {
+    for (before, after, expected) in [
+        ("wA", "Aww", "uÂw"),
+        ("ywi", "EWWd", "yuiEWd"),
+        ("wD", "Ww", "uDw"),
+    ] {
+        let mut engine = VitypeEngine::new();
+        let mut text = Vec::new();
+        type_keys(&mut engine, &mut text, before);
+        if let Some(action) = engine.force_vietnamese(false) {
+            apply(&mut text, action);
+        }
+        type_keys(&mut engine, &mut text, after);
+        assert_eq!(string(&text), expected, "{before}+{after}");
+        assert!(engine.check_provenance().is_ok(), "{before}+{after}");
+
+        match engine.delete_last_character() {
+            Some(action) => apply(&mut text, action),
+            None => {
+                text.pop();
+            }
+        }
+        assert!(engine.check_provenance().is_ok(), "{before}+{after}");
+    }
+}
+
+#[test]
+fn testRememberedPrefixIsNotKeptAtCommit() {
+    let mut engine = VitypeEngine::new();
+    let mut text = Vec::new();
+    type_keys(&mut engine, &mut text, "caaull");
+    apply(&mut text, engine.force_vietnamese(true).unwrap());
+    type_keys(&mut engine, &mut text, " caaull ");
+    assert_eq!(string(&text), "câull câull ");
+
+    // "câul" is only a prefix of the remembered word.
+    text.clear();
+    type_keys(&mut engine, &mut text, "caaul ");
+    assert_eq!(string(&text), "caaul ");
+    assert!(engine.check_provenance().is_ok());
+}
+
+#[test]
+fn testRememberedWordWithDifferentTailGoesForeign() {
+    let mut engine = VitypeEngine::new();
+    let mut text = Vec::new();
+    type_keys(&mut engine, &mut text, "caaul");
+    apply(&mut text, engine.force_vietnamese(true).unwrap());
+    type_keys(&mut engine, &mut text, " caal ");
+    assert_eq!(string(&text), "câul caal ");
+}
@@ -0,0 +1,77 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::{action, type_text};
+use crate::han_nom::{HanNomTable, DEFAULT_HAN_NOM_TABLE};
+use crate::VitypeEngine;
+
+fn engine_with_han_nom() -> VitypeEngine {
+    let mut engine = VitypeEngine::new();
+    engine.set_han_nom_enabled(true);
+    engine
+}
+
+#[test]
+fn testBundledTableLookup() {
+    assert_eq!(DEFAULT_HAN_NOM_TABLE.lookup("nhân"), ["人", "仁", "因"]);
+    assert_eq!(DEFAULT_HAN_NOM_TABLE.lookup("Người")[0], "𠊛");
+    // Lookups normalise tone placement.
+    assert_eq!(DEFAULT_HAN_NOM_TABLE.lookup("hoả"), ["火"]);
+    assert!(DEFAULT_HAN_NOM_TABLE.lookup("xyz").is_empty());
+}
+
+#[test]
+fn testCommitOpensCandidatesAndSelectReplacesWord() {
+    let mut engine = engine_with_han_nom();
+    type_text(&mut engine, "nhaan ");
+    assert_eq!(engine.han_nom_candidates(), ["人", "仁", "因"]);
+    assert_eq!(engine.select_han_nom_candidate(1), Some(action(5, "仁 ")));
+    assert!(engine.han_nom_candidates().is_empty());
+    assert_eq!(engine.history_text(), "仁 ");
+}
+
+#[test]
+fn testDisabledByDefault() {
+    let mut engine = VitypeEngine::new();
+    type_text(&mut engine, "nhaan ");
+    assert!(engine.han_nom_candidates().is_empty());
+}
+
+#[test]
+fn testTriggerOnCurrentWord() {
+    let mut engine = VitypeEngine::new();
+    type_text(&mut engine, "vieetj");
+    assert_eq!(engine.open_han_nom_candidates(), 2);
+    assert_eq!(engine.select_han_nom_candidate(0), Some(action(4, "越")));
+    assert_eq!(engine.buffer.iter().collect::<String>(), "越");
+}
+
+#[test]
+fn testPaging() {
+    let mut engine = engine_with_han_nom();
+    engine.set_han_nom_page_size(2);
+    type_text(&mut engine, "tuwj.");
+    assert_eq!(engine.han_nom_candidates(), ["字", "自"]);
+    assert!(!engine.han_nom_previous_page());
+    assert!(engine.han_nom_next_page());
+    assert_eq!(engine.han_nom_candidates(), ["寺"]);
+    assert!(!engine.han_nom_next_page());
+    assert_eq!(engine.select_han_nom_candidate(0), Some(action(3, "寺.")));
+}
+
+#[test]
+fn testNextKeystrokeDismissesCandidates() {
+    let mut engine = engine_with_han_nom();
+    type_text(&mut engine, "nam ");
+    assert!(!engine.han_nom_candidates().is_empty());
+    engine.process("v");
+    assert!(engine.han_nom_candidates().is_empty());
+    assert_eq!(engine.select_han_nom_candidate(0), None);
+}
+
+#[test]
+fn testCustomTable() {
+    let mut engine = engine_with_han_nom();
+    engine.set_han_nom_table(HanNomTable::parse("# custom\nnam\t喃 南\n"));
+    type_text(&mut engine, "nam ");
+    assert_eq!(engine.han_nom_candidates(), ["喃", "南"]);
+}
@@ -0,0 +1,77 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::{action, type_text};
+use crate::normalize::normalize_i_y;
+use crate::{IYStyle, VitypeEngine};
+
+fn type_with_style(input: &str, style: IYStyle) -> String {
+    let mut engine = VitypeEngine::new();
+    engine.set_i_y_style(style);
+    type_text(&mut engine, input)
+}
+
+#[test]
+fn testNormalizePreferI() {
+    assert_eq!(
+        normalize_i_y("kỹ thuật, lý do, mỹ thuật, tỷ lệ", IYStyle::PreferI),
+        "kĩ thuật, lí do, mĩ thuật, tỉ lệ"
+    );
+}
+
+#[test]
+fn testNormalizePreferY() {
+    assert_eq!(
+        normalize_i_y("kĩ sư, lí lẽ, hi vọng", IYStyle::PreferY),
+        "kỹ sư, lý lẽ, hy vọng"
+    );
+}
+
+#[test]
+fn testClustersAndOtherOnsetsAreUntouched() {
+    let text = "thúy quý tay gì đi kim bí";
+    assert_eq!(normalize_i_y(text, IYStyle::PreferY), text);
+    let text = "thúy quý tay ý";
+    assert_eq!(normalize_i_y(text, IYStyle::PreferI), text);
+}
+
+#[test]
+fn testUnchangedAndCasePreserved() {
+    assert_eq!(normalize_i_y("kỹ Kĩ", IYStyle::Unchanged), "kỹ Kĩ");
+    assert_eq!(normalize_i_y("KỸ Lý", IYStyle::PreferI), "KĨ Lí");
+}
+
+#[test]
+fn testTypingAppliesStyleAtWordBoundary() {
+    assert_eq!(type_with_style("kyx thuaatj", IYStyle::PreferI), "kĩ thuật");
+    assert_eq!(type_with_style("lis do ", IYStyle::PreferY), "lý do ");
+    assert_eq!(type_with_style("kix ", IYStyle::Unchanged), "kĩ ");
+    assert_eq!(type_with_style("kim ", IYStyle::PreferY), "kim ");
+}
+
+#[test]
+fn testBoundaryActionIncludesBoundaryCharacter() {
+    let mut engine = VitypeEngine::new();
+    engine.set_i_y_style(IYStyle::PreferY);
+    engine.process("l");
+    engine.process("i");
+    engine.process("s");
+    assert_eq!(engine.process(","), Some(action(1, "ý,")));
+    assert_eq!(engine.history_text(), "lý,");
+}
+
+#[test]
+fn testBackspaceAfterCommitKeepsRestyledWord() {
+    let mut engine = VitypeEngine::new();
+    engine.set_i_y_style(IYStyle::PreferI);
+    engine.set_smart_backspace(true);
+    for ch in "kyx ".chars() {
+        engine.process(&ch.to_string());
+    }
+    assert_eq!(engine.delete_last_character(), None);
+    assert_eq!(engine.buffer.iter().collect::<String>(), "kĩ");
+    assert_eq!(engine.raw_buffer.iter().collect::<String>(), "kix");
+    assert_eq!(engine.delete_last_character(), Some(action(1, "i")));
+    assert_eq!(engine.delete_last_character(), None);
+    assert_eq!(engine.buffer.iter().collect::<String>(), "k");
+    assert!(engine.check_provenance().is_ok());
+}
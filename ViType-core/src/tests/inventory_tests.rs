@@ -0,0 +1,166 @@
+#![allow(non_snake_case)]
+
+use std::collections::HashSet;
+
+use crate::inventory::all_syllables;
+use crate::{InputMethod, TonePlacement, VitypeEngine};
+
+/// Telex keystrokes for a lowercase syllable: letters, shape keys after each
+/// vowel, and the tone key last.
+fn telex_keys(word: &str) -> String {
+    let mut keys = String::new();
+    let mut tone_key: Option<char> = None;
+    for ch in word.chars() {
+        let (base, tone) = crate::diacritics::split_vowel_and_tone(ch);
+        if tone.is_some() {
+            tone_key = tone;
+        }
+        match base {
+            'ă' => keys.push_str("aw"),
+            'â' => keys.push_str("aa"),
+            'ê' => keys.push_str("ee"),
+            'ô' => keys.push_str("oo"),
+            'ơ' => keys.push_str("ow"),
+            'ư' => keys.push_str("uw"),
+            'đ' => keys.push_str("dd"),
+            _ => keys.push(base),
+        }
+    }
+    keys.extend(tone_key);
+    keys
+}
+
+/// VNI keystrokes for a lowercase syllable: letters, shape digits after each
+/// vowel, and the tone digit last.
+fn vni_keys(word: &str) -> String {
+    let mut keys = String::new();
+    let mut tone_key: Option<char> = None;
+    for ch in word.chars() {
+        let (base, tone) = crate::diacritics::split_vowel_and_tone(ch);
+        if let Some(tone) = tone {
+            tone_key = match tone {
+                's' => Some('1'),
+                'f' => Some('2'),
+                'r' => Some('3'),
+                'x' => Some('4'),
+                'j' => Some('5'),
+                _ => None,
+            };
+        }
+        match base {
+            'ă' => keys.push_str("a8"),
+            'â' => keys.push_str("a6"),
+            'ê' => keys.push_str("e6"),
+            'ô' => keys.push_str("o6"),
+            'ơ' => keys.push_str("o7"),
+            'ư' => keys.push_str("u7"),
+            'đ' => keys.push_str("d9"),
+            _ => keys.push(base),
+        }
+    }
+    keys.extend(tone_key);
+    keys
+}
+
+fn type_word(keys: &str, input_method: InputMethod, tone_placement: TonePlacement) -> String {
+    let mut engine = VitypeEngine::new();
+    engine.input_method = input_method;
+    engine.tone_placement = tone_placement;
+    for key in keys.chars() {
+        engine.process(&key.to_string());
+    }
+    engine.buffer.iter().collect()
+}
+
+#[test]
+fn testEveryEntryValidates() {
+    let mut count = 0;
+    for entry in all_syllables() {
+        let chars: Vec<char> = entry.text.chars().collect();
+        assert!(
+            matches!(
+                VitypeEngine::for_word(&chars, entry.tone_placement).validate_syllable(),
+                Ok(Some(_))
+            ),
+            "{}",
+            entry.text
+        );
+        count += 1;
+    }
+    assert!(count > 10_000, "{count}");
+}
+
+#[test]
+fn testContainsKnownSyllablesInBothPlacements() {
+    let entries: HashSet<(String, TonePlacement)> = all_syllables()
+        .map(|entry| (entry.text, entry.tone_placement))
+        .collect();
+    for word in ["nghiêng", "quyển", "giữa", "đường", "hòa", "gì", "thuở"] {
+        assert!(
+            entries.contains(&(word.to_string(), TonePlacement::Orthographic)),
+            "{word}"
+        );
+    }
+    assert!(entries.contains(&("hoà".to_string(), TonePlacement::NucleusOnly)));
+    assert!(!entries.contains(&("hòa".to_string(), TonePlacement::NucleusOnly)));
+    assert!(!entries
+        .iter()
+        .any(|(word, _)| word == "ci" || word == "cê" || word == "ngi"));
+    assert!(!entries
+        .iter()
+        .any(|(word, _)| word == "àc" || word == "bảt"));
+}
+
+#[test]
+fn testNoDuplicates() {
+    let mut seen = HashSet::new();
+    for entry in all_syllables() {
+        assert!(
+            seen.insert((entry.text.clone(), entry.tone_placement)),
+            "{}",
+            entry.text
+        );
+    }
+}
+
+#[test]
+fn testTelexRoundTrip() {
+    // Telex turns "uow" into "ươ", so "uơ" (thuở) is only reachable after q.
+    let failures: Vec<String> = all_syllables()
+        .filter(|entry| entry.nucleus != "uơ")
+        .filter(|entry| {
+            type_word(
+                &telex_keys(&entry.text),
+                InputMethod::Telex,
+                entry.tone_placement,
+            ) != entry.text
+        })
+        .map(|entry| entry.text)
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "{:?}",
+        &failures[..failures.len().min(20)]
+    );
+}
+
+#[test]
+fn testVniRoundTrip() {
+    // VNI puts the horn of "uo7" on both vowels, so "uơ" is left out as in Telex.
+    let failures: Vec<String> = all_syllables()
+        .filter(|entry| entry.nucleus != "uơ")
+        .filter(|entry| {
+            type_word(
+                &vni_keys(&entry.text),
+                InputMethod::Vni,
+                entry.tone_placement,
+            ) != entry.text
+        })
+        .map(|entry| entry.text)
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "{:?}",
+        &failures[..failures.len().min(20)]
+    );
+}
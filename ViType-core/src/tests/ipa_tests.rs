@@ -0,0 +1,75 @@
+#![allow(non_snake_case)]
+
+use crate::ipa::{transcribe, transcribe_syllable, Dialect};
+
+/// Reference transcriptions: (word, Hanoi, Saigon).
+const REFERENCE: [(&str, &str, &str); 18] = [
+    ("ba", "ɓaː˧˧", "ɓaː˧˧"),
+    ("nhà", "ɲaː˨˩", "ɲaː˨˩"),
+    ("tiếng", "tiəŋ˧˥", "tiəŋ˧˥"),
+    ("Việt", "viət˨˩", "jiək˨˩"),
+    ("quả", "kwaː˧˩˧", "waː˨˩˦"),
+    ("gì", "zi˨˩", "ji˨˩"),
+    ("giếng", "ziəŋ˧˥", "jiəŋ˧˥"),
+    ("rồi", "zoj˨˩", "ɹoj˨˩"),
+    ("dạ", "zaː˨˩ˀ", "jaː˨˩˨"),
+    ("anh", "ɛɲ˧˧", "ăn˧˧"),
+    ("sân", "sə̆n˧˧", "ʂə̆ŋ˧˧"),
+    ("trường", "cɨəŋ˨˩", "ʈɨəŋ˨˩"),
+    ("ngã", "ŋaː˧ˀ˥", "ŋaː˨˩˦"),
+    ("khỏe", "xwɛ˧˩˧", "xwɛ˨˩˦"),
+    ("thuyền", "tʰwiən˨˩", "tʰwiəŋ˨˩"),
+    ("học", "hɔk˨˩", "hɔk˨˩"),
+    ("tín", "tin˧˥", "tin˧˥"),
+    ("sách", "sɛc˦˥", "ʂăt˦˥"),
+];
+
+#[test]
+fn testReferenceTableHanoi() {
+    for (word, hanoi, _) in REFERENCE {
+        let chars: Vec<char> = word.chars().collect();
+        let syllable = transcribe_syllable(&chars, Dialect::Hanoi);
+        assert_eq!(
+            syllable.map(|parts| parts.to_ipa()).as_deref(),
+            Some(hanoi),
+            "{word}"
+        );
+    }
+}
+
+#[test]
+fn testReferenceTableSaigon() {
+    for (word, _, saigon) in REFERENCE {
+        let chars: Vec<char> = word.chars().collect();
+        let syllable = transcribe_syllable(&chars, Dialect::Saigon);
+        assert_eq!(
+            syllable.map(|parts| parts.to_ipa()).as_deref(),
+            Some(saigon),
+            "{word}"
+        );
+    }
+}
+
+#[test]
+fn testPartsAreSplit() {
+    let chars: Vec<char> = "hoàng".chars().collect();
+    let syllable = transcribe_syllable(&chars, Dialect::Hanoi).unwrap();
+    assert_eq!(syllable.onset, "h");
+    assert_eq!(syllable.glide, "w");
+    assert_eq!(syllable.nucleus, "aː");
+    assert_eq!(syllable.coda, "ŋ");
+    assert_eq!(syllable.tone, "˨˩");
+}
+
+#[test]
+fn testBothTonePlacementsParse() {
+    assert_eq!(transcribe("hoà hòa", Dialect::Hanoi), "hwaː˨˩ hwaː˨˩");
+}
+
+#[test]
+fn testUnparseableTokensReturnedAsIs() {
+    assert_eq!(
+        transcribe("đi học, hello xyz 42!", Dialect::Hanoi),
+        "ɗi˧˧ hɔk˨˩, hello xyz 42!"
+    );
+}
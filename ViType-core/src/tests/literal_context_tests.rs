@@ -0,0 +1,76 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::{backspace, string, type_keys};
+use crate::literal_context::LiteralContextOptions;
+use crate::VitypeEngine;
+
+const BACKSPACE: char = '\u{8}';
+
+fn type_with_options(input: &str, options: LiteralContextOptions) -> String {
+    let mut engine = VitypeEngine::new();
+    engine.set_literal_context_options(options);
+    let mut output: Vec<char> = Vec::new();
+    for ch in input.chars() {
+        if ch == BACKSPACE {
+            backspace(&mut engine, &mut output);
+        } else {
+            type_keys(&mut engine, &mut output, &ch.to_string());
+        }
+    }
+    string(&output)
+}
+
+fn type_text(input: &str) -> String {
+    type_with_options(input, LiteralContextOptions::default())
+}
+
+#[test]
+fn testUrlStaysLiteral() {
+    assert_eq!(type_text("https://vieetj.com"), "https://vieetj.com");
+    assert_eq!(type_text("ftp://hoaf.vn/tieengs"), "ftp://hoaf.vn/tieengs");
+}
+
+#[test]
+fn testEmailRestoresLocalPart() {
+    assert_eq!(type_text("user@host"), "user@host");
+    assert_eq!(type_text("hoas@mail.com"), "hoas@mail.com");
+}
+
+#[test]
+fn testPathStaysLiteral() {
+    assert_eq!(type_text("/usr/local"), "/usr/local");
+    assert_eq!(type_text("~/tieengs"), "~/tieengs");
+}
+
+#[test]
+fn testIdentifiersStayLiteral() {
+    assert_eq!(type_text("my_var_name"), "my_var_name");
+    assert_eq!(type_text("getUser.ddaa"), "getUser.ddaa");
+}
+
+#[test]
+fn testWhitespaceEndsLiteralContext() {
+    assert_eq!(type_text("my_var vieetj"), "my_var việt");
+    assert_eq!(type_text("a@b\ntoois"), "a@b\ntối");
+}
+
+#[test]
+fn testPlainPunctuationKeepsTelex() {
+    assert_eq!(type_text("vieetj. Nam"), "việt. Nam");
+    assert_eq!(type_text("chaof, banj"), "chào, bạn");
+}
+
+#[test]
+fn testDeletingTriggerLeavesContext() {
+    assert_eq!(type_text("x_\u{8}\u{8}as"), "á");
+}
+
+#[test]
+fn testHeuristicsAreConfigurable() {
+    let options = LiteralContextOptions {
+        identifiers: false,
+        ..LiteralContextOptions::default()
+    };
+    assert_eq!(type_with_options("my_vaf", options), "my_và");
+    assert_eq!(type_with_options("a@vaf", options), "a@vaf");
+}
@@ -0,0 +1,75 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::type_text;
+use crate::loanword::{LoanwordLexicon, TokenPolicy, BUNDLED_LOANWORDS};
+use crate::VitypeEngine;
+
+fn type_with_lexicon(input: &str, lexicon: Option<LoanwordLexicon>) -> String {
+    let mut engine = VitypeEngine::new();
+    if let Some(lexicon) = lexicon {
+        engine.set_loanword_lexicon(lexicon);
+    }
+    type_text(&mut engine, input)
+}
+
+fn type_with_bundled(input: &str) -> String {
+    type_with_lexicon(input, Some(BUNDLED_LOANWORDS.clone()))
+}
+
+#[test]
+fn testParseRejectsMalformedLines() {
+    assert!(LoanwordLexicon::parse("pizza\tbogus").is_none());
+    assert!(LoanwordLexicon::parse("ok\toverride").is_none());
+    assert!(LoanwordLexicon::parse("# comment only\n\n").is_some());
+}
+
+#[test]
+fn testLiteralTokensStayAsTyped() {
+    assert_eq!(type_with_lexicon("wifi", None), "ừii");
+    assert_eq!(type_with_bundled("wifi"), "wifi");
+    assert_eq!(type_with_bundled("karaoke"), "karaoke");
+    assert_eq!(type_with_bundled("pizza"), "pizza");
+    assert_eq!(type_with_bundled("email "), "email ");
+}
+
+#[test]
+fn testLiteralMatchingIgnoresCase() {
+    assert_eq!(type_with_bundled("Wifi"), "Wifi");
+    assert_eq!(type_with_bundled("KARAOKE"), "KARAOKE");
+}
+
+#[test]
+fn testLeavingLiteralTokenRestoresVietnamese() {
+    assert_eq!(type_with_bundled("ems"), "ém");
+    assert_eq!(type_with_bundled("vis"), "ví");
+    assert_eq!(type_with_bundled("wis"), "ứi");
+}
+
+#[test]
+fn testShortPrefixesKeepTelexMeaning() {
+    assert_eq!(type_with_bundled("w"), "ư");
+}
+
+#[test]
+fn testOverrideReplacesCompleteToken() {
+    assert_eq!(type_with_lexicon("xoong", None), "xông");
+    assert_eq!(type_with_bundled("xoong"), "xoong");
+    assert_eq!(type_with_bundled("ok"), "OK");
+    assert_eq!(type_with_bundled("xoongf"), "xồng");
+}
+
+#[test]
+fn testVietnamesePolicySuppressesForeignFallback() {
+    assert_eq!(type_with_lexicon("balf", None), "balf");
+
+    let mut lexicon = LoanwordLexicon::default();
+    lexicon.insert("Balf", TokenPolicy::Vietnamese);
+    assert_eq!(type_with_lexicon("balf", Some(lexicon)), "bàl");
+}
+
+#[test]
+fn testMinimumPrefixLengthIsConfigurable() {
+    let mut lexicon = BUNDLED_LOANWORDS.clone();
+    lexicon.min_prefix_length = 1;
+    assert_eq!(type_with_lexicon("w", Some(lexicon)), "w");
+}
@@ -0,0 +1,38 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::{apply_input, type_text};
+use crate::VitypeEngine;
+
+fn apply_input_with_mixed_case(input: &str) -> String {
+    let mut engine = VitypeEngine::new();
+    engine.set_mixed_case_is_foreign(true);
+    type_text(&mut engine, input)
+}
+
+#[test]
+fn testInternalCapitalRestoresRawKeystrokes() {
+    assert_eq!(apply_input_with_mixed_case("ViEETJ"), "ViEETJ");
+    assert_eq!(apply_input_with_mixed_case("dDaa"), "dDaa");
+    assert_eq!(apply_input_with_mixed_case("hoAf"), "hoAf");
+    assert_eq!(apply_input_with_mixed_case("xBoox"), "xBoox");
+}
+
+#[test]
+fn testMixedCaseWordsStayLiteral() {
+    assert_eq!(apply_input_with_mixed_case("iPhone"), "iPhone");
+    assert_eq!(apply_input_with_mixed_case("macOS"), "macOS");
+    assert_eq!(apply_input_with_mixed_case("getUser"), "getUser");
+}
+
+#[test]
+fn testAllCapsAndTitleCaseStillTransform() {
+    assert_eq!(apply_input_with_mixed_case("TIEENGS"), "TIẾNG");
+    assert_eq!(apply_input_with_mixed_case("Tieengs"), "Tiếng");
+    assert_eq!(apply_input_with_mixed_case("DDAAYS"), "ĐẤY");
+}
+
+#[test]
+fn testMixedCaseDetectionIsOffByDefault() {
+    assert_eq!(apply_input("ViEETJ"), "ViỆT");
+    assert_eq!(apply_input("hoAf"), "hòA");
+}
@@ -1,8 +1,38 @@
+mod caret_tests;
+mod collation_tests;
+mod confusable_tests;
+mod edit_script_tests;
+mod force_vietnamese_tests;
+// The baseline suites mirror the Swift tests, nested modules and all.
+#[allow(clippy::module_inception)]
 mod foreign_consonant_tests;
+mod han_nom_tests;
+mod i_y_style_tests;
+mod inventory_tests;
+mod ipa_tests;
+#[allow(clippy::module_inception)]
 mod key_transformer_tests;
+mod literal_context_tests;
+mod loanword_tests;
+mod mixed_case_tests;
+mod normalize_tests;
+mod orthography_tests;
+mod predict_tests;
+mod preedit_tests;
+mod provenance_tests;
+mod restore_tests;
+mod session_tests;
+mod smart_backspace_tests;
+mod spellcheck_tests;
+mod surrounding_tests;
 mod test_helpers;
 mod tone_cluster_tests;
+#[allow(clippy::bool_assert_comparison)]
 mod tone_placement_tests;
+mod unconverted_tests;
+mod undo_tests;
 mod vni_legacy_tests_do_not_edit_or_update;
+#[allow(clippy::module_inception, clippy::bool_assert_comparison)]
 mod vni_mapping_tests;
+mod vni_numeric_context_tests;
 mod w_transform_tests;
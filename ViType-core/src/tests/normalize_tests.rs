@@ -0,0 +1,58 @@
+#![allow(non_snake_case)]
+
+use crate::normalize::normalize_tone_placement;
+use crate::TonePlacement;
+
+#[test]
+fn testNormalizeToOrthographic() {
+    assert_eq!(
+        normalize_tone_placement("hoà thuý khoẻ", TonePlacement::Orthographic),
+        "hòa thúy khỏe"
+    );
+}
+
+#[test]
+fn testNormalizeToNucleusOnly() {
+    assert_eq!(
+        normalize_tone_placement("hòa thúy khỏe", TonePlacement::NucleusOnly),
+        "hoà thuý khoẻ"
+    );
+}
+
+#[test]
+fn testFinalConsonantAndShapedVowelsAreStable() {
+    let text = "hoàn thuyết người quốc giữa";
+    assert_eq!(
+        normalize_tone_placement(text, TonePlacement::Orthographic),
+        text
+    );
+    assert_eq!(
+        normalize_tone_placement(text, TonePlacement::NucleusOnly),
+        text
+    );
+}
+
+#[test]
+fn testQuAndGiExceptions() {
+    assert_eq!(
+        normalize_tone_placement("qúa gìa", TonePlacement::Orthographic),
+        "quá già"
+    );
+}
+
+#[test]
+fn testPreservesCaseAndSeparators() {
+    assert_eq!(
+        normalize_tone_placement("HOÀ, Thuý!\n", TonePlacement::Orthographic),
+        "HÒA, Thúy!\n"
+    );
+}
+
+#[test]
+fn testNonVietnameseTokensAreUntouched() {
+    let text = "café naïve wìfi hoàe";
+    assert_eq!(
+        normalize_tone_placement(text, TonePlacement::Orthographic),
+        text
+    );
+}
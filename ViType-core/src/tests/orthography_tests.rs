@@ -0,0 +1,117 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::type_text;
+use crate::orthography::{OrthographyPack, CENTRAL_HIGHLANDS_PACK};
+use crate::{InputMethod, VitypeEngine};
+
+fn type_with_pack(input: &str, input_method: InputMethod) -> String {
+    type_with(&CENTRAL_HIGHLANDS_PACK, input, input_method)
+}
+
+fn type_with(pack: &OrthographyPack, input: &str, input_method: InputMethod) -> String {
+    let mut engine = VitypeEngine::new();
+    engine.input_method = input_method;
+    engine.add_orthography_pack(pack.clone());
+    type_text(&mut engine, input)
+}
+
+#[test]
+fn testBundledPackParses() {
+    let pack = &*CENTRAL_HIGHLANDS_PACK;
+    assert_eq!(pack.name, "Central Highlands");
+    assert_eq!(pack.letters.len(), 7);
+    assert!(pack.relaxations.allow_multiple_vowel_clusters);
+    assert!(pack
+        .relaxations
+        .extra_final_consonants
+        .contains(&"h".to_string()));
+}
+
+#[test]
+fn testParseRejectsMalformedLines() {
+    assert!(OrthographyPack::parse("letter ŏ o").is_none());
+    assert!(OrthographyPack::parse("letter ŏ oo v 8").is_none());
+    assert!(OrthographyPack::parse("unknown directive").is_none());
+    assert!(OrthographyPack::parse("final y").is_none());
+    assert!(OrthographyPack::parse("final").is_none());
+    assert!(OrthographyPack::parse("# only a comment\n").is_some());
+}
+
+#[test]
+fn testTelexPackLetters() {
+    assert_eq!(type_with_pack("hov", InputMethod::Telex), "hŏ");
+    assert_eq!(type_with_pack("ƀ", InputMethod::Telex), "ƀ");
+    assert_eq!(type_with_pack("bvuon", InputMethod::Telex), "ƀuon");
+    assert_eq!(type_with_pack("mnuvih", InputMethod::Telex), "mnŭih");
+    assert_eq!(type_with_pack("Ov", InputMethod::Telex), "Ŏ");
+    assert_eq!(type_with_pack("anvuh", InputMethod::Telex), "añuh");
+    // The tilde of ñ is part of the letter, not a ngã tone.
+    assert_eq!(type_with_pack("nvas", InputMethod::Telex), "ñá");
+}
+
+#[test]
+fn testVniPackLetters() {
+    assert_eq!(type_with_pack("ho8", InputMethod::Vni), "hŏ");
+    assert_eq!(type_with_pack("c9ih", InputMethod::Vni), "čih");
+    assert_eq!(type_with_pack("d9i", InputMethod::Vni), "đi");
+    assert_eq!(type_with_pack("la8m", InputMethod::Vni), "lăm");
+}
+
+#[test]
+fn testPackVowelsTakeTones() {
+    let pack = OrthographyPack::parse("letter ü u v 8\n").expect("valid pack");
+    assert_eq!(type_with(&pack, "luvs", InputMethod::Telex), "lǘ");
+    assert_eq!(type_with(&pack, "Luvf", InputMethod::Telex), "Lǜ");
+    // A toned base keeps its tone when it becomes the pack letter.
+    assert_eq!(type_with(&pack, "lusv", InputMethod::Telex), "lǘ");
+    assert_eq!(type_with(&pack, "lu81", InputMethod::Vni), "lǘ");
+    assert_eq!(type_with(&pack, "ngu8i1", InputMethod::Vni), "ngǘi");
+}
+
+#[test]
+fn testPackVowelsWithoutPrecomposedTonesRejectTheTone() {
+    // ŏ́ would need a combining sequence, which letters cannot hold yet, so the
+    // tone key is typed as a letter and the word keeps ŏ.
+    assert_eq!(type_with_pack("hovs", InputMethod::Telex), "hŏs");
+    assert_eq!(type_with_pack("hovsa", InputMethod::Telex), "hŏsa");
+    assert_eq!(type_with_pack("ho81", InputMethod::Vni), "hŏ1");
+    let pack = OrthographyPack::parse("letter ü u v 8\n").expect("valid pack");
+    assert_eq!(type_with(&pack, "luvx", InputMethod::Telex), "lüx");
+}
+
+#[test]
+fn testRepeatedTriggerEscapes() {
+    assert_eq!(type_with_pack("ovv", InputMethod::Telex), "ov");
+    assert_eq!(type_with_pack("o88", InputMethod::Vni), "o8");
+}
+
+#[test]
+fn testRelaxationsKeepPackWordsVietnamese() {
+    // "h" is not a Vietnamese final, but the pack allows it, so tones still apply.
+    assert_eq!(type_with_pack("bvahs", InputMethod::Telex), "ƀáh");
+    assert_eq!(type_with_pack("cvehs", InputMethod::Telex), "čéh");
+}
+
+#[test]
+fn testRelaxationsOnlyApplyToWordsWithPackLetters() {
+    assert_eq!(type_with_pack("ahs", InputMethod::Telex), "ahs");
+
+    let mut engine = VitypeEngine::new();
+    engine.add_orthography_pack(CENTRAL_HIGHLANDS_PACK.clone());
+    for ch in "buna".chars() {
+        engine.process(&ch.to_string());
+    }
+    assert!(engine.is_foreign_mode);
+
+    engine.reset();
+    for ch in "bvuna".chars() {
+        engine.process(&ch.to_string());
+    }
+    assert!(!engine.is_foreign_mode);
+}
+
+#[test]
+fn testStandardTelexUnchangedWithoutPack() {
+    assert_eq!(super::test_helpers::apply_input("hov"), "hov");
+    assert_eq!(super::test_helpers::apply_input("ehs"), "ehs");
+}
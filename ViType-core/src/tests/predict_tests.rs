@@ -0,0 +1,95 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::{action, type_text};
+use crate::lexicon::Lexicon;
+use crate::predict::PredictionKind;
+use crate::VitypeEngine;
+
+const CORPUS: &str = "người Việt nói tiếng Việt. người Việt Nam yêu nước. \
+                      tiếng Việt hay. người ta nói nhiều. Việt Nam đẹp";
+
+fn engine_with_corpus() -> VitypeEngine {
+    let mut model = Lexicon::default();
+    model.train(CORPUS);
+    let mut engine = VitypeEngine::new();
+    engine.set_prediction_model(model);
+    engine
+}
+
+fn texts(engine: &mut VitypeEngine, limit: usize) -> Vec<String> {
+    engine
+        .predictions(limit)
+        .iter()
+        .map(|prediction| prediction.text.clone())
+        .collect()
+}
+
+#[test]
+fn testTrainCountsSyllablesAndBigrams() {
+    let mut model = Lexicon::default();
+    model.train(CORPUS);
+    assert_eq!(model.unigram_count("việt"), 5);
+    assert_eq!(model.bigram_count("người", "việt"), 2);
+    // Sentence punctuation breaks bigrams.
+    assert_eq!(model.bigram_count("việt", "người"), 0);
+}
+
+#[test]
+fn testCompletesCurrentSyllableFromPlainLetters() {
+    let mut engine = engine_with_corpus();
+    type_text(&mut engine, "ngu");
+    assert_eq!(texts(&mut engine, 3), vec!["người"]);
+    assert_eq!(engine.predictions(1)[0].kind, PredictionKind::Completion);
+}
+
+#[test]
+fn testCompletionKeepsCase() {
+    let mut engine = engine_with_corpus();
+    type_text(&mut engine, "Vi");
+    assert_eq!(texts(&mut engine, 3), vec!["Việt"]);
+}
+
+#[test]
+fn testPredictsNextSyllableAfterCommittedWord() {
+    let mut engine = engine_with_corpus();
+    type_text(&mut engine, "Vieejt ");
+    assert_eq!(texts(&mut engine, 2), vec!["nam", "nói"]);
+    assert_eq!(engine.predictions(1)[0].kind, PredictionKind::NextSyllable);
+}
+
+#[test]
+fn testNoNextSyllableAfterSentencePunctuation() {
+    let mut engine = engine_with_corpus();
+    type_text(&mut engine, "Vieejt. ");
+    assert!(texts(&mut engine, 3).is_empty());
+}
+
+#[test]
+fn testAcceptReplacesWordAndCommits() {
+    let mut engine = engine_with_corpus();
+    type_text(&mut engine, "nguo");
+    engine.predictions(3);
+    assert_eq!(engine.accept_prediction(0), Some(action(4, "người ")));
+    assert_eq!(engine.history_text(), "người ");
+    assert_eq!(texts(&mut engine, 3), vec!["việt", "ta"]);
+    assert_eq!(engine.accept_prediction(1), Some(action(0, "ta ")));
+    assert_eq!(engine.accept_prediction(5), None);
+}
+
+#[test]
+fn testRejectHidesSuggestionUntilNextKeystroke() {
+    let mut engine = engine_with_corpus();
+    type_text(&mut engine, "nguowif ");
+    assert_eq!(texts(&mut engine, 3), vec!["việt", "ta"]);
+    assert_eq!(engine.reject_prediction(0), None);
+    assert_eq!(texts(&mut engine, 3), vec!["ta"]);
+    type_text(&mut engine, "v");
+    assert_eq!(texts(&mut engine, 3), vec!["việt"]);
+}
+
+#[test]
+fn testBundledModelIsUsedByDefault() {
+    let mut engine = VitypeEngine::new();
+    type_text(&mut engine, "khon");
+    assert!(texts(&mut engine, 5).contains(&"không".to_string()));
+}
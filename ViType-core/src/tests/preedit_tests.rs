@@ -0,0 +1,94 @@
+#![allow(non_snake_case)]
+
+use crate::preedit::PreeditUpdate;
+use crate::VitypeEngine;
+
+fn update(preedit: &str, commit: &str) -> PreeditUpdate {
+    PreeditUpdate {
+        preedit: preedit.to_string(),
+        caret: preedit.chars().count(),
+        commit: commit.to_string(),
+    }
+}
+
+/// Types `input` in preedit mode and returns the committed text and the final
+/// composing string.
+fn type_preedit(engine: &mut VitypeEngine, input: &str) -> (String, String) {
+    let mut committed = String::new();
+    let mut preedit = String::new();
+    for ch in input.chars() {
+        let update = engine.process_preedit(&ch.to_string());
+        committed.push_str(&update.commit);
+        preedit = update.preedit;
+    }
+    (committed, preedit)
+}
+
+#[test]
+fn testKeysUpdateComposingString() {
+    let mut engine = VitypeEngine::new();
+    assert_eq!(engine.process_preedit("v"), update("v", ""));
+    assert_eq!(engine.process_preedit("i"), update("vi", ""));
+    assert_eq!(engine.process_preedit("e"), update("vie", ""));
+    assert_eq!(engine.process_preedit("e"), update("viê", ""));
+    assert_eq!(engine.process_preedit("t"), update("viêt", ""));
+    assert_eq!(engine.process_preedit("j"), update("việt", ""));
+}
+
+#[test]
+fn testBoundaryCommitsWordAndBoundary() {
+    let mut engine = VitypeEngine::new();
+    type_preedit(&mut engine, "tieengs");
+    assert_eq!(engine.process_preedit(" "), update("", "tiếng "));
+    assert_eq!(engine.process_preedit(","), update("", ","));
+}
+
+#[test]
+fn testTypingSentence() {
+    let mut engine = VitypeEngine::new();
+    let (committed, preedit) = type_preedit(&mut engine, "xin chaof, tieengs Vieetj");
+    assert_eq!(committed, "xin chào, tiếng ");
+    assert_eq!(preedit, "Việt");
+}
+
+#[test]
+fn testForeignFallbackStaysInPreedit() {
+    let mut engine = VitypeEngine::new();
+    let (committed, preedit) = type_preedit(&mut engine, "hotdogs");
+    assert_eq!(committed, "");
+    assert_eq!(preedit, "hotdogs");
+}
+
+#[test]
+fn testBoundaryCommitIncludesIYRestyle() {
+    let mut engine = VitypeEngine::new();
+    engine.set_i_y_style(crate::IYStyle::PreferI);
+    type_preedit(&mut engine, "kyx");
+    assert_eq!(engine.process_preedit(" "), update("", "kĩ "));
+}
+
+#[test]
+fn testLiteralContextCommitsRawWord() {
+    let mut engine = VitypeEngine::new();
+    let (committed, preedit) = type_preedit(&mut engine, "hoas@mail");
+    assert_eq!(committed, "hoas@");
+    assert_eq!(preedit, "mail");
+}
+
+#[test]
+fn testExplicitCommit() {
+    let mut engine = VitypeEngine::new();
+    type_preedit(&mut engine, "ddaay");
+    assert_eq!(engine.commit_preedit(), update("", "đây"));
+    assert_eq!(engine.process_preedit("a"), update("a", ""));
+}
+
+#[test]
+fn testBackspaceInsideComposingString() {
+    let mut engine = VitypeEngine::new();
+    type_preedit(&mut engine, "vieetj");
+    assert_eq!(engine.delete_preedit_character(), Some(update("việ", "")));
+    engine.process_preedit(" ");
+    assert_eq!(engine.delete_preedit_character(), None);
+    assert_eq!(engine.process_preedit("a"), update("a", ""));
+}
@@ -0,0 +1,162 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::type_text;
+use crate::provenance::MAX_WORD_SNAPSHOTS;
+use crate::VitypeEngine;
+
+fn buffer(engine: &VitypeEngine) -> String {
+    engine.buffer.iter().collect()
+}
+
+fn raw(engine: &VitypeEngine) -> String {
+    engine.raw_buffer.iter().collect()
+}
+
+#[test]
+fn testProvenanceGroupsTransformKeysWithTheirLetter() {
+    let mut engine = VitypeEngine::new();
+    assert_eq!(type_text(&mut engine, "vieejt"), "việt");
+    assert_eq!(
+        engine.provenance,
+        vec![vec![0], vec![1], vec![2, 3, 4], vec![5]]
+    );
+}
+
+#[test]
+fn testHornKeySharedByTwoVowels() {
+    let mut engine = VitypeEngine::new();
+    assert_eq!(type_text(&mut engine, "uow"), "ươ");
+    assert_eq!(engine.provenance, vec![vec![0, 2], vec![1, 2]]);
+}
+
+#[test]
+fn testBackspaceKeepsToneTypedAfterTheDeletedLetter() {
+    let mut engine = VitypeEngine::new();
+    type_text(&mut engine, "vieetj");
+    engine.delete_last_character();
+    assert_eq!(buffer(&engine), "việ");
+    assert_eq!(raw(&engine), "vieej");
+    assert_eq!(engine.check_provenance(), Ok(()));
+}
+
+#[test]
+fn testBackspaceRemovesAllKeysOfStrokedD() {
+    let mut engine = VitypeEngine::new();
+    type_text(&mut engine, "ddi");
+    engine.delete_last_character();
+    assert_eq!(buffer(&engine), "đ");
+    assert_eq!(raw(&engine), "dd");
+    engine.delete_last_character();
+    assert_eq!(buffer(&engine), "");
+    assert_eq!(raw(&engine), "");
+}
+
+#[test]
+fn testBackspaceRestoresLastTransformKey() {
+    let mut engine = VitypeEngine::new();
+    type_text(&mut engine, "aan");
+    engine.delete_last_character();
+    assert_eq!(buffer(&engine), "â");
+    assert_eq!(engine.last_transform_key, Some('a'));
+    // The repeated key still escapes the circumflex.
+    type_text(&mut engine, "a");
+    assert_eq!(buffer(&engine), "aa");
+}
+
+#[test]
+fn testBackspaceRestoresSuppressedTransformKey() {
+    let mut engine = VitypeEngine::new();
+    type_text(&mut engine, "aaan");
+    assert_eq!(buffer(&engine), "aan");
+    engine.delete_last_character();
+    assert_eq!(buffer(&engine), "aa");
+    assert_eq!(engine.suppressed_transform_key, Some('a'));
+    // Still suppressed, so the next "a" does not rebuild the circumflex.
+    type_text(&mut engine, "a");
+    assert_eq!(buffer(&engine), "aaa");
+}
+
+#[test]
+fn testBackspaceInWordRestoredFromHistory() {
+    let mut engine = VitypeEngine::new();
+    type_text(&mut engine, "vieetj ");
+    engine.delete_last_character();
+    engine.delete_last_character();
+    assert_eq!(buffer(&engine), "việ");
+    assert_eq!(raw(&engine), "vieej");
+    type_text(&mut engine, "n");
+    assert_eq!(buffer(&engine), "viện");
+}
+
+#[test]
+fn testBackspaceThroughLongWordsStaysConsistent() {
+    for keys in [
+        "nghieengs",
+        "khuyeenx",
+        "truowngf",
+        "nguyeenx",
+        "ddaauf",
+        "thuowr",
+    ] {
+        let mut engine = VitypeEngine::new();
+        let typed: Vec<char> = type_text(&mut engine, keys).chars().collect();
+        for length in (0..typed.len()).rev() {
+            engine.delete_last_character();
+            assert_eq!(engine.check_provenance(), Ok(()), "{keys}");
+            assert_eq!(engine.buffer, typed[..length], "{keys}");
+        }
+        assert!(engine.raw_buffer.is_empty(), "{keys}");
+    }
+}
+
+#[test]
+fn testHanNomCharacterOwnsEveryKeystroke() {
+    let mut engine = VitypeEngine::new();
+    type_text(&mut engine, "vieetj");
+    engine.open_han_nom_candidates();
+    engine.select_han_nom_candidate(0);
+    assert_eq!(buffer(&engine), "越");
+    assert_eq!(engine.provenance, vec![vec![0, 1, 2, 3, 4, 5]]);
+    assert!(engine.check_provenance().is_ok());
+}
+
+#[test]
+fn testLiteralWordsMapKeystrokesOneToOne() {
+    let mut engine = VitypeEngine::new();
+    type_text(&mut engine, "https://vieetj");
+    assert_eq!(buffer(&engine), raw(&engine));
+    assert_eq!(
+        engine.provenance,
+        (0..engine.raw_buffer.len())
+            .map(|index| vec![index])
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn testRepairFixesBrokenProvenanceWithoutPanicking() {
+    let mut engine = VitypeEngine::new();
+    type_text(&mut engine, "tieeng");
+    engine.provenance = vec![vec![0]];
+    assert!(engine.repair_provenance().is_err());
+    assert!(engine.check_provenance().is_ok());
+    assert!(engine.repair_provenance().is_ok());
+    // Backspace still works on the repaired word.
+    engine.delete_last_character();
+    assert_eq!(buffer(&engine), "tiên");
+    assert!(engine.check_provenance().is_ok());
+}
+
+#[test]
+fn testLongWordKeepsBoundedSnapshots() {
+    let mut engine = VitypeEngine::new();
+    let keys = "vieetj".repeat(20);
+    type_text(&mut engine, &keys);
+    assert!(engine.word_snapshots.len() <= MAX_WORD_SNAPSHOTS);
+
+    // Backspace past the oldest snapshot falls back to provenance.
+    while !engine.buffer.is_empty() {
+        engine.delete_last_character();
+        assert!(engine.check_provenance().is_ok());
+    }
+}
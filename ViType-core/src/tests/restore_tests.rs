@@ -0,0 +1,122 @@
+#![allow(non_snake_case)]
+
+use crate::lexicon::{Lexicon, DEFAULT_LEXICON};
+use crate::restore::restore_diacritics;
+use crate::TonePlacement;
+
+fn restore(text: &str) -> String {
+    restore_diacritics(text, &DEFAULT_LEXICON, TonePlacement::Orthographic).text
+}
+
+#[test]
+fn testRestoresCommonSentences() {
+    assert_eq!(restore("toi uong sua moi sang"), "tôi uống sữa mỗi sáng");
+    assert_eq!(
+        restore("Gia dinh toi co bon nguoi."),
+        "Gia đình tôi có bốn người."
+    );
+    assert_eq!(
+        restore("Ha Noi la thu do cua Viet Nam"),
+        "Hà Nội là thủ đô của Việt Nam"
+    );
+}
+
+#[test]
+fn testContextPicksBetweenHomographs() {
+    assert_eq!(restore("tho sua xe"), "thợ sửa xe");
+    assert_eq!(restore("uong sua"), "uống sữa");
+}
+
+#[test]
+fn testNonVietnameseTokensAreKept() {
+    assert_eq!(restore("hello world, email"), "hello world, email");
+    assert_eq!(restore("Tôi co nhieu viec"), "Tôi có nhiều việc");
+}
+
+#[test]
+fn testLongTokensAreKeptQuickly() {
+    // A letter-by-letter product of shape options would take seconds here.
+    let long = "a".repeat(40);
+    assert_eq!(restore(&long), long);
+    assert_eq!(
+        restore("aaaaaaaaaaaaa dddddddddddd"),
+        "aaaaaaaaaaaaa dddddddddddd"
+    );
+}
+
+#[test]
+fn testTokensReportConfidence() {
+    let restoration = restore_diacritics(
+        "viet nam zoom",
+        &DEFAULT_LEXICON,
+        TonePlacement::Orthographic,
+    );
+    assert_eq!(restoration.tokens.len(), 3);
+    assert_eq!(restoration.tokens[0].text, "việt");
+    assert!(restoration.tokens[0].confidence > 0.5);
+    assert!(restoration.tokens[0].confidence <= 1.0);
+    assert_eq!(restoration.tokens[2].text, "zoom");
+    assert_eq!(restoration.tokens[2].confidence, 1.0);
+}
+
+#[test]
+fn testUnknownWordsStillGetValidSyllables() {
+    let restoration = restore_diacritics("hoa", &Lexicon::default(), TonePlacement::NucleusOnly);
+    assert_eq!(restoration.text, "hoa");
+    assert!(restoration.tokens[0].confidence < 0.5);
+}
+
+#[test]
+fn testBinaryLexiconRoundTrip() {
+    let bytes = DEFAULT_LEXICON.to_bytes();
+    assert!(bytes.starts_with(b"VTLM"));
+    let decoded = Lexicon::from_bytes(&bytes).expect("valid lexicon");
+    assert_eq!(
+        decoded.unigram_count("tôi"),
+        DEFAULT_LEXICON.unigram_count("tôi")
+    );
+    assert_eq!(
+        decoded.bigram_count("uống", "sữa"),
+        DEFAULT_LEXICON.bigram_count("uống", "sữa")
+    );
+    assert!(Lexicon::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+    assert!(Lexicon::from_bytes(b"nope").is_none());
+}
+
+#[test]
+fn testLexiconLoadsFromFile() {
+    let path = std::env::temp_dir().join(format!("vitype-lexicon-{}.bin", std::process::id()));
+    let lexicon = Lexicon::parse("mèo\t3\ncon\t5\ncon mèo\t3\n");
+    lexicon.save(&path).expect("write lexicon");
+    let loaded = Lexicon::load(&path).expect("read lexicon");
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(
+        restore_diacritics("con meo", &loaded, TonePlacement::Orthographic).text,
+        "con mèo"
+    );
+}
+
+#[test]
+fn testLexiconCountsSaturate() {
+    let mut lexicon = Lexicon::parse("con\t5\ncon mèo\t3\ncon chó\t2\nmèo con\t1\n");
+    lexicon.add_unigram("con", u32::MAX);
+    lexicon.add_bigram("con", "mèo", u32::MAX);
+    assert_eq!(lexicon.unigram_count("con"), u32::MAX);
+    assert_eq!(lexicon.bigram_count("con", "mèo"), u32::MAX);
+
+    let mut followers: Vec<(&str, u32)> = lexicon.followers("con").collect();
+    followers.sort();
+    assert_eq!(followers, [("chó", 2), ("mèo", u32::MAX)]);
+}
+
+#[test]
+fn testBundledBinaryMatchesTextSource() {
+    // Rebuild data/lexicon.vtlm with `Lexicon::parse(..).save(..)` after editing the text.
+    let source = Lexicon::parse(include_str!("../../data/lexicon.txt"));
+    assert_eq!(
+        source.to_bytes(),
+        include_bytes!("../../data/lexicon.vtlm").to_vec()
+    );
+    assert_eq!(DEFAULT_LEXICON.unigram_count("và"), 100000);
+}
@@ -0,0 +1,124 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::type_text;
+use crate::common::InputMethod;
+use crate::session::SessionManager;
+use crate::VitypeEngine;
+
+fn buffer(engine: &VitypeEngine) -> String {
+    engine.buffer.iter().collect()
+}
+
+#[test]
+fn testSwitchingBackKeepsWordInProgress() {
+    let mut manager = SessionManager::new();
+    type_text(manager.activate("editor"), "vieej");
+    type_text(manager.activate("search"), "xin");
+
+    let engine = manager.activate("editor");
+    assert_eq!(buffer(engine), "việ");
+    assert_eq!(type_text(engine, "t"), "t");
+    assert_eq!(buffer(engine), "việt");
+}
+
+#[test]
+fn testSwitchingBackKeepsBackspaceRestore() {
+    let mut manager = SessionManager::new();
+    type_text(manager.activate("editor"), "vieetj ");
+    type_text(manager.activate("search"), "abc");
+
+    let engine = manager.activate("editor");
+    engine.delete_last_character();
+    assert_eq!(buffer(engine), "việt");
+}
+
+#[test]
+fn testEvictsLeastRecentlyActivated() {
+    let mut manager = SessionManager::new();
+    manager.set_max_sessions(2);
+    type_text(manager.activate("a"), "toi");
+    type_text(manager.activate("b"), "ban");
+    manager.activate("a");
+    manager.activate("c");
+    assert_eq!(manager.session_count(), 2);
+
+    assert_eq!(buffer(manager.activate("a")), "toi");
+    assert_eq!(buffer(manager.activate("b")), "");
+}
+
+#[test]
+fn testLoweringLimitEvictsImmediately() {
+    let mut manager = SessionManager::new();
+    for context in ["a", "b", "c", "d"] {
+        manager.activate(context);
+    }
+    manager.set_max_sessions(1);
+    assert_eq!(manager.session_count(), 1);
+    assert!(manager.active_engine_mut().is_some());
+}
+
+#[test]
+fn testDropSession() {
+    let mut manager = SessionManager::new();
+    type_text(manager.activate("a"), "toi");
+    assert!(manager.drop_session("a"));
+    assert!(!manager.drop_session("a"));
+    assert!(manager.active_engine_mut().is_none());
+    assert_eq!(buffer(manager.activate("a")), "");
+
+    manager.activate("b");
+    manager.drop_all_sessions();
+    assert_eq!(manager.session_count(), 0);
+}
+
+#[test]
+fn testDeactivateKeepsSession() {
+    let mut manager = SessionManager::new();
+    type_text(manager.activate("a"), "toi");
+    manager.deactivate();
+    assert!(manager.active_engine_mut().is_none());
+    assert_eq!(buffer(manager.activate("a")), "toi");
+}
+
+#[test]
+fn testSessionsShareConfiguration() {
+    let mut manager = SessionManager::new();
+    type_text(manager.activate("a"), "vieetj ");
+    manager
+        .configuration_mut()
+        .set_input_method(InputMethod::Vni);
+    manager.configuration_mut().set_smart_backspace(true);
+    manager.apply_configuration();
+
+    assert_eq!(type_text(manager.active_engine_mut().unwrap(), "a6"), "â");
+    assert_eq!(type_text(manager.activate("b"), "o7"), "ơ");
+    assert!(manager.activate("a").smart_backspace);
+}
+
+#[test]
+fn testConfigurationChangesReachEverySession() {
+    let mut manager = SessionManager::new();
+    for context in ["a", "b", "c"] {
+        manager.activate(context);
+    }
+
+    // The active session takes the change at once, the others on activation.
+    manager.configuration_mut().set_smart_backspace(true);
+    manager.apply_configuration();
+    assert!(manager.active_engine_mut().unwrap().smart_backspace);
+    for context in ["a", "b", "c"] {
+        assert!(manager.activate(context).smart_backspace, "{context}");
+    }
+
+    // A setting changed on one session's engine is replaced by the shared one.
+    manager.activate("a").set_smart_backspace(false);
+    manager
+        .configuration_mut()
+        .set_input_method(InputMethod::Vni);
+    manager.apply_configuration();
+    for context in ["a", "b", "c"] {
+        let engine = manager.activate(context);
+        assert!(engine.smart_backspace, "{context}");
+        assert_eq!(engine.input_method, InputMethod::Vni, "{context}");
+    }
+}
@@ -0,0 +1,96 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::Host;
+use crate::{InputMethod, VitypeEngine};
+
+fn host() -> Host {
+    let mut engine = VitypeEngine::new();
+    engine.set_smart_backspace(true);
+    Host::new(engine)
+}
+
+#[test]
+fn testPeelsToneThenShapeThenLetters() {
+    let mut host = host();
+    host.type_keys("vieejt");
+    assert_eq!(host.backspace(), "viêt");
+    assert_eq!(host.backspace(), "viet");
+    assert_eq!(host.backspace(), "vie");
+    assert_eq!(host.backspace(), "vi");
+}
+
+#[test]
+fn testFollowsTheOrderMarksWereApplied() {
+    // The tone comes before the circumflex here, so it survives longer.
+    let mut host = host();
+    host.type_keys("vijeet");
+    assert_eq!(host.text(), "việt");
+    assert_eq!(host.backspace(), "viẹt");
+    assert_eq!(host.backspace(), "viet");
+}
+
+#[test]
+fn testRemovesStrokeFromD() {
+    let mut host = host();
+    host.type_keys("ddi");
+    assert_eq!(host.backspace(), "di");
+    assert_eq!(host.backspace(), "d");
+}
+
+#[test]
+fn testHornOnBothVowelsIsOneMark() {
+    let mut host = host();
+    host.type_keys("dduowcj");
+    assert_eq!(host.text(), "được");
+    assert_eq!(host.backspace(), "đươc");
+    assert_eq!(host.backspace(), "đuoc");
+    assert_eq!(host.backspace(), "duoc");
+    assert_eq!(host.backspace(), "duo");
+}
+
+#[test]
+fn testTypingContinuesAfterPeeling() {
+    let mut host = host();
+    host.type_keys("toans");
+    assert_eq!(host.backspace(), "toan");
+    host.type_keys("f");
+    assert_eq!(host.text(), "toàn");
+}
+
+#[test]
+fn testPeelsWordRestoredFromHistory() {
+    let mut host = host();
+    host.type_keys("vieejt ");
+    assert_eq!(host.backspace(), "việt");
+    assert_eq!(host.backspace(), "viêt");
+}
+
+#[test]
+fn testForeignWordsLoseLetters() {
+    let mut host = host();
+    host.type_keys("karaoke");
+    assert_eq!(host.backspace(), "karaok");
+}
+
+#[test]
+fn testOffByDefault() {
+    let mut engine = VitypeEngine::new();
+    for ch in "vieejt".chars() {
+        engine.process(&ch.to_string());
+    }
+    assert!(engine.delete_last_character().is_none());
+    assert_eq!(engine.buffer.iter().collect::<String>(), "việ");
+}
+
+#[test]
+fn testReplaysUnderNumericContext() {
+    // "75" are literal digits, so only the tone and the circumflex are marks.
+    let mut host = host();
+    host.engine.input_method = InputMethod::Vni;
+    host.engine.set_smart_numeric_context(true);
+    host.type_keys("ba6175");
+    assert_eq!(host.text(), "bấ75");
+    assert_eq!(host.backspace(), "bâ75");
+    assert_eq!(host.backspace(), "ba75");
+    assert_eq!(host.backspace(), "ba7");
+}
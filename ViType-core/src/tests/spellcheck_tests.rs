@@ -0,0 +1,98 @@
+#![allow(non_snake_case)]
+
+use crate::syllable::SyllableError;
+use crate::{TonePlacement, VitypeEngine};
+
+fn check(text: &str) -> Vec<(String, SyllableError, Vec<String>)> {
+    let engine = VitypeEngine::new();
+    engine
+        .check_text(text)
+        .into_iter()
+        .map(|issue| {
+            (
+                text[issue.byte_start..issue.byte_end].to_string(),
+                issue.kind,
+                issue.suggestions,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn testValidTextHasNoIssues() {
+    assert!(check("Tiếng Việt là ngôn ngữ của người Việt.").is_empty());
+    assert!(check("khuya quốc gia giữa nghiêng hoạch").is_empty());
+}
+
+#[test]
+fn testForeignAndAbbreviationTokensAreSkipped() {
+    assert!(check("wifi TP HCM zoom").is_empty());
+}
+
+#[test]
+fn testInvalidClusterIsReported() {
+    let issues = check("xin chàoe");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].0, "chàoe");
+    assert_eq!(issues[0].1, SyllableError::InvalidCluster);
+}
+
+#[test]
+fn testInvalidOnsetSuggestsSpellingRule() {
+    let issues = check("cem");
+    assert_eq!(issues[0].1, SyllableError::InvalidOnset);
+    assert_eq!(issues[0].2[0], "kem");
+
+    let issues = check("ngiêng");
+    assert_eq!(issues[0].1, SyllableError::InvalidOnset);
+    assert_eq!(issues[0].2[0], "nghiêng");
+}
+
+#[test]
+fn testInvalidCodaIsReported() {
+    let issues = check("bănh");
+    assert_eq!(issues[0].1, SyllableError::InvalidCoda);
+    assert_eq!(issues[0].2, ["băn", "băng", "banh", "băc", "băk"]);
+}
+
+#[test]
+fn testToneOnStopFinalIsReported() {
+    let issues = check("hòc");
+    assert_eq!(issues[0].1, SyllableError::StopFinalTone);
+    assert_eq!(issues[0].2[..2], ["hóc".to_string(), "học".to_string()]);
+}
+
+#[test]
+fn testMisplacedToneFollowsActivePlacement() {
+    let issues = check("hoà");
+    assert_eq!(issues[0].1, SyllableError::MisplacedTone);
+    assert_eq!(issues[0].2[0], "hòa");
+
+    let mut engine = VitypeEngine::new();
+    engine.tone_placement = TonePlacement::NucleusOnly;
+    assert!(engine.check_text("hoà").is_empty());
+    let issues = engine.check_text("hòa");
+    assert_eq!(issues[0].kind, SyllableError::MisplacedTone);
+    assert_eq!(issues[0].suggestions[0], "hoà");
+}
+
+#[test]
+fn testSpansReportBytesAndChars() {
+    let engine = VitypeEngine::new();
+    let issues = engine.check_text("Đây là hòc sinh");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].char_start, 7);
+    assert_eq!(issues[0].char_end, 10);
+    assert_eq!(issues[0].byte_start, "Đây là ".len());
+    assert_eq!(issues[0].byte_end, "Đây là hòc".len());
+}
+
+#[test]
+fn testSuggestionsKeepCapitalisation() {
+    let issues = check("Hòc HÒC");
+    assert_eq!(issues[0].2[0].chars().next(), Some('H'));
+    assert!(issues[1]
+        .2
+        .iter()
+        .all(|word| word.chars().all(|ch| !ch.is_lowercase())));
+}
@@ -0,0 +1,62 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::{string, type_keys};
+use crate::{InputMethod, VitypeEngine};
+
+/// Syncs to `before`, types `keys` and returns the resulting text.
+fn type_after(engine: &mut VitypeEngine, before: &str, keys: &str) -> String {
+    engine.sync_surrounding_text(before);
+    let mut output: Vec<char> = before.chars().collect();
+    type_keys(engine, &mut output, keys);
+    string(&output)
+}
+
+#[test]
+fn testMatchingTextIsLeftAlone() {
+    let mut engine = VitypeEngine::new();
+    for ch in "xin chaof".chars() {
+        engine.process(&ch.to_string());
+    }
+    assert!(!engine.sync_surrounding_text("Tôi nói: xin chào"));
+    assert!(engine.sync_surrounding_text("Tôi nói: xin chàoo"));
+    assert!(engine.sync_surrounding_text("bxin chào"));
+}
+
+#[test]
+fn testCurrentWordKeepsTakingMarks() {
+    let mut engine = VitypeEngine::new();
+    assert_eq!(type_after(&mut engine, "Tiêng", "s"), "Tiếng");
+    assert_eq!(type_after(&mut engine, "tieng Viêt", "j"), "tieng Việt");
+    assert_eq!(type_after(&mut engine, "đương", "f"), "đường");
+    assert_eq!(type_after(&mut engine, "hoà", "n"), "hoàn");
+}
+
+#[test]
+fn testInferredRawAllowsEscape() {
+    let mut engine = VitypeEngine::new();
+    assert_eq!(type_after(&mut engine, "hoạt", "j"), "hoatj");
+    assert_eq!(engine.raw_buffer, "hoatj".chars().collect::<Vec<_>>());
+}
+
+#[test]
+fn testHistoryIsRebuilt() {
+    let mut engine = VitypeEngine::new();
+    engine.sync_surrounding_text("một hai ");
+    engine.delete_last_character();
+    engine.process("s");
+    assert_eq!(engine.buffer, "hái".chars().collect::<Vec<_>>());
+}
+
+#[test]
+fn testVniKeystrokes() {
+    let mut engine = VitypeEngine::new();
+    engine.input_method = InputMethod::Vni;
+    assert_eq!(type_after(&mut engine, "Việt", "1"), "Viết");
+    assert_eq!(engine.raw_buffer, "Vie6t51".chars().collect::<Vec<_>>());
+}
+
+#[test]
+fn testUnreproducibleWordIsLocked() {
+    let mut engine = VitypeEngine::new();
+    assert_eq!(type_after(&mut engine, "naïve", "s"), "naïves");
+}
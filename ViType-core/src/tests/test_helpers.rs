@@ -3,8 +3,145 @@
 //
 // Created by Tran Dat on 24/12/25.
 
+use crate::edit_script::EditOp;
 use crate::{InputMethod, KeyTransformAction, TonePlacement, VitypeEngine};
 
+/// Types `keys` into `engine` and mirrors each action onto `text` the way a host would.
+pub(super) fn type_keys(engine: &mut VitypeEngine, text: &mut Vec<char>, keys: &str) {
+    for ch in keys.chars() {
+        match engine.process(&ch.to_string()) {
+            Some(action) => apply(text, action),
+            None => text.push(ch),
+        }
+    }
+}
+
+/// Types `keys` into `engine` and returns the text a host would show for them.
+pub(super) fn type_text(engine: &mut VitypeEngine, keys: &str) -> String {
+    let mut text = Vec::new();
+    type_keys(engine, &mut text, keys);
+    string(&text)
+}
+
+pub(super) fn apply(text: &mut Vec<char>, action: KeyTransformAction) {
+    for _ in 0..action.delete_count {
+        text.pop();
+    }
+    text.extend(action.text.chars());
+}
+
+pub(super) fn string(text: &[char]) -> String {
+    text.iter().collect()
+}
+
+/// Sends a backspace to `engine` and mirrors it onto `text`: the engine's action
+/// when it returns one, otherwise the host deletes the last character itself.
+pub(super) fn backspace(engine: &mut VitypeEngine, text: &mut Vec<char>) {
+    match engine.delete_last_character() {
+        Some(action) => apply(text, action),
+        None => {
+            text.pop();
+        }
+    }
+}
+
+/// Applies an edit script to `text` at `caret`, moving the caret as a host would.
+pub(super) fn apply_ops(text: &mut Vec<char>, caret: &mut usize, ops: &[EditOp]) {
+    for op in ops {
+        match op {
+            EditOp::MoveLeft(count) => *caret -= count,
+            EditOp::Delete(count) => {
+                text.drain(*caret - count..*caret);
+                *caret -= count;
+            }
+            EditOp::Insert(inserted) => {
+                for ch in inserted.chars() {
+                    text.insert(*caret, ch);
+                    *caret += 1;
+                }
+            }
+            EditOp::MoveRight(count) => *caret += count,
+        }
+    }
+}
+
+/// A host text field that mirrors every key, backspace and caret move.
+pub(super) struct Host {
+    pub(super) engine: VitypeEngine,
+    pub(super) text: Vec<char>,
+    pub(super) caret: usize,
+}
+
+impl Host {
+    pub(super) fn new(engine: VitypeEngine) -> Host {
+        Host {
+            engine,
+            text: Vec::new(),
+            caret: 0,
+        }
+    }
+
+    /// Types `keys` at the end of the text with `process`.
+    pub(super) fn type_keys(&mut self, keys: &str) -> &mut Host {
+        type_keys(&mut self.engine, &mut self.text, keys);
+        self.caret = self.text.len();
+        self
+    }
+
+    /// Types `keys` at the caret with `process_at_caret`.
+    pub(super) fn type_at_caret(&mut self, keys: &str) -> &mut Host {
+        for ch in keys.chars() {
+            match self.engine.process_at_caret(&ch.to_string()) {
+                Some(ops) => apply_ops(&mut self.text, &mut self.caret, &ops),
+                None => {
+                    self.text.insert(self.caret, ch);
+                    self.caret += 1;
+                }
+            }
+        }
+        self
+    }
+
+    /// Backspace at the end of the text; returns the text afterwards.
+    pub(super) fn backspace(&mut self) -> String {
+        backspace(&mut self.engine, &mut self.text);
+        self.caret = self.text.len();
+        self.text()
+    }
+
+    pub(super) fn move_by(&mut self, delta: isize) -> &mut Host {
+        let moved = self.engine.move_cursor(delta);
+        self.caret = (self.caret as isize + moved) as usize;
+        self
+    }
+
+    pub(super) fn delete_forward(&mut self) -> &mut Host {
+        if let Some(ops) = self.engine.delete_forward() {
+            apply_ops(&mut self.text, &mut self.caret, &ops);
+        }
+        self
+    }
+
+    pub(super) fn delete_backward(&mut self) -> &mut Host {
+        if let Some(ops) = self.engine.delete_backward() {
+            apply_ops(&mut self.text, &mut self.caret, &ops);
+        }
+        self
+    }
+
+    pub(super) fn text(&self) -> String {
+        string(&self.text)
+    }
+
+    /// The text with `|` at the caret.
+    pub(super) fn show(&self) -> String {
+        let mut shown: String = self.text[..self.caret].iter().collect();
+        shown.push('|');
+        shown.extend(&self.text[self.caret..]);
+        shown
+    }
+}
+
 pub(super) fn action(delete_count: usize, text: &str) -> KeyTransformAction {
     KeyTransformAction {
         delete_count,
@@ -0,0 +1,125 @@
+#![allow(non_snake_case)]
+
+use crate::unconverted::best_conversion;
+use crate::{InputMethod, VitypeEngine};
+
+fn scan(engine: &VitypeEngine, text: &str) -> Vec<(String, InputMethod, String)> {
+    engine
+        .find_unconverted_words(text)
+        .into_iter()
+        .map(|hit| {
+            (
+                text[hit.byte_start..hit.byte_end].to_string(),
+                hit.input_method,
+                hit.converted,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn testFindsTelexLeftovers() {
+    let engine = VitypeEngine::new();
+    assert_eq!(
+        scan(&engine, "Tiếng vieejt rất hay, ddi thôi"),
+        vec![
+            ("vieejt".to_string(), InputMethod::Telex, "việt".to_string()),
+            ("ddi".to_string(), InputMethod::Telex, "đi".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn testFindsVniLeftovers() {
+    let engine = VitypeEngine::new();
+    assert_eq!(
+        scan(&engine, "Tie61ng vie6t5"),
+        vec![
+            ("Tie61ng".to_string(), InputMethod::Vni, "Tiếng".to_string()),
+            ("vie6t5".to_string(), InputMethod::Vni, "việt".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn testKeepsConvertedAndForeignText() {
+    let engine = VitypeEngine::new();
+    assert!(scan(&engine, "Tiếng Việt, an toàn").is_empty());
+    assert!(scan(&engine, "as is of the world").is_empty());
+    assert!(scan(&engine, "Phong 302, v2 a1b2c3 mp3").is_empty());
+}
+
+#[test]
+fn testTelexLeftoversNeedVietnameseText() {
+    let engine = VitypeEngine::new();
+    assert!(scan(&engine, "cos").is_empty());
+    assert!(scan(&engine, "ddi").is_empty());
+    assert_eq!(
+        scan(&engine, "cos ddi thôi"),
+        vec![
+            ("cos".to_string(), InputMethod::Telex, "có".to_string()),
+            ("ddi".to_string(), InputMethod::Telex, "đi".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn testEnglishWordsAreNotFlagged() {
+    let engine = VitypeEngine::new();
+    for word in ["see", "been", "moon", "door", "has"] {
+        assert!(scan(&engine, word).is_empty(), "{word}");
+    }
+    assert!(scan(&engine, "I have been to the moon, see the door").is_empty());
+}
+
+#[test]
+fn testSyllablesMissingFromTheLexiconAreFound() {
+    let engine = VitypeEngine::new();
+    assert_eq!(
+        scan(&engine, "Tiếng Việt: tie6ng, ddaan"),
+        vec![
+            ("tie6ng".to_string(), InputMethod::Vni, "tiêng".to_string()),
+            ("ddaan".to_string(), InputMethod::Telex, "đân".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn testActiveMethodBreaksTies() {
+    let both = || {
+        vec![
+            (InputMethod::Telex, "đi".chars().collect::<Vec<char>>()),
+            (InputMethod::Vni, "đi".chars().collect()),
+        ]
+    };
+    assert_eq!(
+        best_conversion(both(), InputMethod::Vni).map(|(method, _)| method),
+        Some(InputMethod::Vni)
+    );
+    assert_eq!(
+        best_conversion(both(), InputMethod::Telex).map(|(method, _)| method),
+        Some(InputMethod::Telex)
+    );
+}
+
+#[test]
+fn testKnownWordOutranksActiveMethod() {
+    let conversions = vec![
+        (InputMethod::Telex, "việt".chars().collect::<Vec<char>>()),
+        (InputMethod::Vni, "viêt".chars().collect()),
+    ];
+    assert_eq!(
+        best_conversion(conversions, InputMethod::Vni).map(|(method, _)| method),
+        Some(InputMethod::Telex)
+    );
+}
+
+#[test]
+fn testOtherMethodIsFoundWhenActiveOneFails() {
+    let mut engine = VitypeEngine::new();
+    engine.input_method = InputMethod::Vni;
+    let hits = engine.find_unconverted_words("ddi thôi");
+    assert_eq!(hits[0].input_method, InputMethod::Telex);
+    assert_eq!(hits[0].char_start, 0);
+    assert_eq!(hits[0].char_end, 3);
+}
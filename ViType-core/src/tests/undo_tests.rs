@@ -0,0 +1,139 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::{apply, string, type_keys};
+use crate::VitypeEngine;
+
+fn typed(keys: &str) -> (VitypeEngine, Vec<char>) {
+    let mut engine = VitypeEngine::new();
+    let mut text = Vec::new();
+    type_keys(&mut engine, &mut text, keys);
+    (engine, text)
+}
+
+mod undo_last_transform_tests {
+    use super::*;
+
+    #[test]
+    fn testRevertsToneLikeEscapeKey() {
+        let (mut engine, mut text) = typed("vieejt");
+        apply(&mut text, engine.undo_last_transform().unwrap());
+        assert_eq!(string(&text), "viêtj");
+    }
+
+    #[test]
+    fn testMatchesTypingTheEscapeKey() {
+        let (mut engine, mut text) = typed("bans");
+        apply(&mut text, engine.undo_last_transform().unwrap());
+
+        let (_, escaped) = typed("banss");
+        assert_eq!(string(&text), string(&escaped));
+    }
+
+    #[test]
+    fn testRevertsStroke() {
+        let (mut engine, mut text) = typed("dd");
+        apply(&mut text, engine.undo_last_transform().unwrap());
+        assert_eq!(string(&text), "dd");
+    }
+
+    #[test]
+    fn testLocksTheWord() {
+        let (mut engine, mut text) = typed("as");
+        apply(&mut text, engine.undo_last_transform().unwrap());
+        assert_eq!(string(&text), "as");
+        type_keys(&mut engine, &mut text, "aa");
+        assert_eq!(string(&text), "asaa");
+    }
+
+    #[test]
+    fn testOnlyTheNewestTransform() {
+        let (mut engine, mut text) = typed("tooi");
+        assert_eq!(string(&text), "tôi");
+        apply(&mut text, engine.undo_last_transform().unwrap());
+        assert_eq!(string(&text), "toio");
+    }
+
+    #[test]
+    fn testNothingToUndo() {
+        let (mut engine, _) = typed("ban");
+        assert!(engine.undo_last_transform().is_none());
+        let (mut engine, _) = typed("ban ");
+        assert!(engine.undo_last_transform().is_none());
+    }
+}
+
+mod toggle_raw_last_word_tests {
+    use super::*;
+
+    #[test]
+    fn testShowsRawKeysWithTrailingBoundary() {
+        let (mut engine, mut text) = typed("user, ");
+        assert_eq!(string(&text), "ủe, ");
+        apply(&mut text, engine.toggle_raw_last_word().unwrap());
+        assert_eq!(string(&text), "user, ");
+    }
+
+    #[test]
+    fn testTogglesBack() {
+        let (mut engine, mut text) = typed("user ");
+        apply(&mut text, engine.toggle_raw_last_word().unwrap());
+        apply(&mut text, engine.toggle_raw_last_word().unwrap());
+        assert_eq!(string(&text), "ủe ");
+    }
+
+    #[test]
+    fn testIncludesWordInProgress() {
+        let (mut engine, mut text) = typed("user ok");
+        apply(&mut text, engine.toggle_raw_last_word().unwrap());
+        assert_eq!(string(&text), "user ok");
+        type_keys(&mut engine, &mut text, "s");
+        assert_eq!(string(&text), "user ók");
+    }
+
+    #[test]
+    fn testBackspaceIntoToggledWordKeepsRaw() {
+        let (mut engine, mut text) = typed("user ");
+        apply(&mut text, engine.toggle_raw_last_word().unwrap());
+        engine.delete_last_character();
+        engine.delete_last_character();
+        assert_eq!(string(&engine.buffer), "use");
+    }
+
+    #[test]
+    fn testTogglesEscapedWordToItsReplay() {
+        let (mut engine, mut text) = typed("toss ");
+        assert_eq!(string(&text), "tos ");
+        apply(&mut text, engine.toggle_raw_last_word().unwrap());
+        assert_eq!(string(&text), "tó ");
+        apply(&mut text, engine.toggle_raw_last_word().unwrap());
+        assert_eq!(string(&text), "tos ");
+        assert!(engine.check_provenance().is_ok());
+    }
+
+    #[test]
+    fn testSkipsLiteralContextWords() {
+        // "ass" after "_" stays literal, but replays to the escaped "as".
+        let (mut engine, text) = typed("mix_ass ");
+        assert_eq!(string(&text), "mix_ass ");
+        assert!(engine.toggle_raw_last_word().is_none());
+        assert!(engine.check_provenance().is_ok());
+    }
+
+    #[test]
+    fn testSkipsWordsWithEscapeKeys() {
+        for keys in ["a@Ww ", "me@Ww "] {
+            let (mut engine, text) = typed(keys);
+            assert_eq!(string(&text), keys);
+            assert!(engine.toggle_raw_last_word().is_none(), "{keys}");
+            assert!(engine.check_provenance().is_ok());
+        }
+    }
+
+    #[test]
+    fn testNothingToToggle() {
+        let (mut engine, _) = typed("ban ");
+        assert!(engine.toggle_raw_last_word().is_none());
+        let (mut engine, _) = typed("ban");
+        assert!(engine.toggle_raw_last_word().is_none());
+    }
+}
@@ -0,0 +1,95 @@
+#![allow(non_snake_case)]
+
+use super::test_helpers::{apply_vni_input, string, type_keys};
+use crate::{InputMethod, VitypeEngine};
+
+const SKIP_NUMERIC_CONTEXT: char = '\u{1}';
+
+fn apply_vni_input_with_numeric_context(input: &str) -> String {
+    let mut engine = VitypeEngine::new();
+    engine.input_method = InputMethod::Vni;
+    engine.set_smart_numeric_context(true);
+
+    let mut output: Vec<char> = Vec::new();
+    for ch in input.chars() {
+        if ch == SKIP_NUMERIC_CONTEXT {
+            engine.skip_numeric_context_for_word();
+        } else {
+            type_keys(&mut engine, &mut output, &ch.to_string());
+        }
+    }
+    string(&output)
+}
+
+mod vni_numeric_word_tests {
+    use super::apply_vni_input_with_numeric_context;
+
+    #[test]
+    fn testVniWordStartingWithDigitStaysLiteral() {
+        assert_eq!(apply_vni_input_with_numeric_context("3a1"), "3a1");
+        assert_eq!(apply_vni_input_with_numeric_context("1a2"), "1a2");
+        assert_eq!(apply_vni_input_with_numeric_context("ba2 4o5"), "bà 4o5");
+    }
+
+    #[test]
+    fn testVniDigitAfterDigitStaysLiteral() {
+        assert_eq!(apply_vni_input_with_numeric_context("a06"), "a06");
+        // The "1" was a tone key, so "2" replaces the tone as in plain VNI.
+        assert_eq!(apply_vni_input_with_numeric_context("a12"), "à");
+        assert_eq!(
+            apply_vni_input_with_numeric_context("Phong 302"),
+            "Phong 302"
+        );
+    }
+
+    #[test]
+    fn testVniShapeThenToneDigitsStillTransform() {
+        assert_eq!(apply_vni_input_with_numeric_context("a61 "), "ấ ");
+        assert_eq!(apply_vni_input_with_numeric_context("ca61u "), "cấu ");
+        assert_eq!(apply_vni_input_with_numeric_context("vie65t "), "việt ");
+        assert_eq!(apply_vni_input_with_numeric_context("nu7o71c "), "nước ");
+    }
+
+    #[test]
+    fn testVniSingleToneDigitStillTransforms() {
+        assert_eq!(
+            apply_vni_input_with_numeric_context("vie6t5 na8m"),
+            "việt năm"
+        );
+        assert_eq!(apply_vni_input_with_numeric_context("a1b2c3"), "a1b2c3");
+    }
+}
+
+mod vni_number_pattern_tests {
+    use super::apply_vni_input_with_numeric_context;
+
+    #[test]
+    fn testVniVersionPatternsStayLiteral() {
+        assert_eq!(apply_vni_input_with_numeric_context("v2.0"), "v2.0");
+        assert_eq!(apply_vni_input_with_numeric_context("v1.2o1"), "v1.2o1");
+        assert_eq!(apply_vni_input_with_numeric_context("1.5a1"), "1.5a1");
+        assert_eq!(apply_vni_input_with_numeric_context("3,5"), "3,5");
+    }
+
+    #[test]
+    fn testVniSeparatorAfterWordStillTransforms() {
+        assert_eq!(apply_vni_input_with_numeric_context("a1.a1"), "á.á");
+        assert_eq!(apply_vni_input_with_numeric_context("a1,a1"), "á,á");
+    }
+}
+
+mod vni_numeric_context_settings_tests {
+    use super::{apply_vni_input, apply_vni_input_with_numeric_context, SKIP_NUMERIC_CONTEXT};
+
+    #[test]
+    fn testVniNumericContextIsOffByDefault() {
+        assert_eq!(apply_vni_input("a06"), "â0");
+        assert_eq!(apply_vni_input("3a1"), "3á");
+    }
+
+    #[test]
+    fn testVniSkipNumericContextForWord() {
+        let input = format!("3{}a1 3a1", SKIP_NUMERIC_CONTEXT);
+        assert_eq!(apply_vni_input_with_numeric_context(&input), "3á 3a1");
+    }
+}
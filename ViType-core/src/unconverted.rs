@@ -0,0 +1,106 @@
+use crate::common::{lower_string, InputMethod};
+use crate::lexicon::DEFAULT_LEXICON;
+use crate::syllable::{is_vietnamese_letter, tokens_matching};
+use crate::VitypeEngine;
+
+// ==================== Unconverted Word ====================
+
+/// Raw Telex/VNI keystrokes left in finished text, such as "vieejt" or "vie6t5".
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct UnconvertedWord {
+    pub(crate) byte_start: usize,
+    pub(crate) byte_end: usize,
+    pub(crate) char_start: usize,
+    pub(crate) char_end: usize,
+    pub(crate) input_method: InputMethod,
+    pub(crate) converted: String,
+}
+
+// ==================== Helper Functions ====================
+
+fn is_valid_syllable(word: &[char], engine: &VitypeEngine) -> bool {
+    matches!(
+        VitypeEngine::for_word(word, engine.tone_placement).validate_syllable(),
+        Ok(Some(_))
+    )
+}
+
+/// Picks the conversion of one token: the more frequent word in the lexicon wins,
+/// and the active input method breaks ties, including words the lexicon lacks.
+pub(crate) fn best_conversion(
+    conversions: Vec<(InputMethod, Vec<char>)>,
+    active: InputMethod,
+) -> Option<(InputMethod, Vec<char>)> {
+    conversions.into_iter().max_by_key(|(method, converted)| {
+        (
+            DEFAULT_LEXICON.unigram_count(&lower_string(converted)),
+            *method == active,
+        )
+    })
+}
+
+// ==================== Scanner Methods on VitypeEngine ====================
+
+impl VitypeEngine {
+    /// Replays `raw` as one word with this engine's settings and `input_method`,
+    /// returning what the engine would have produced.
+    fn replay_word(&self, raw: &[char], input_method: InputMethod) -> Vec<char> {
+        let mut engine = VitypeEngine::new();
+        engine.auto_fix_tone = self.auto_fix_tone;
+        engine.tone_placement = self.tone_placement;
+        engine.input_method = input_method;
+        for ch in raw {
+            let _ = engine.process(&ch.to_string());
+        }
+        engine.buffer
+    }
+
+    fn convert_leftover(&self, raw: &[char], input_method: InputMethod) -> Option<Vec<char>> {
+        if input_method == InputMethod::Telex && !raw.iter().all(|ch| ch.is_ascii_alphabetic()) {
+            return None;
+        }
+        let converted = self.replay_word(raw, input_method);
+        if converted == raw || !is_valid_syllable(&converted, self) {
+            return None;
+        }
+        Some(converted)
+    }
+
+    /// Finds ASCII tokens that are not Vietnamese as written but become valid
+    /// syllables when replayed as Telex or VNI keystrokes.
+    pub(crate) fn find_unconverted_words(&self, text: &str) -> Vec<UnconvertedWord> {
+        // Plenty of English words replay into valid syllables ("as" → "á", "been" →
+        // "bên"), so Telex leftovers need Vietnamese text around them. VNI leftovers
+        // carry digits inside the word and stand on their own.
+        let has_vietnamese_text = text
+            .chars()
+            .any(|ch| !ch.is_ascii() && is_vietnamese_letter(ch));
+
+        let mut found = Vec::new();
+        for token in tokens_matching(text, |ch| ch.is_ascii_alphanumeric()) {
+            if !token.chars[0].is_ascii_alphabetic() || is_valid_syllable(&token.chars, self) {
+                continue;
+            }
+            let conversions: Vec<(InputMethod, Vec<char>)> = [InputMethod::Telex, InputMethod::Vni]
+                .into_iter()
+                .filter(|method| *method == InputMethod::Vni || has_vietnamese_text)
+                .filter_map(|method| {
+                    self.convert_leftover(&token.chars, method)
+                        .map(|converted| (method, converted))
+                })
+                .collect();
+            if let Some((input_method, converted)) = best_conversion(conversions, self.input_method)
+            {
+                found.push(UnconvertedWord {
+                    byte_start: token.byte_start,
+                    byte_end: token.byte_end,
+                    char_start: token.char_start,
+                    char_end: token.char_end,
+                    input_method,
+                    converted: converted.into_iter().collect(),
+                });
+            }
+        }
+        found
+    }
+}
@@ -0,0 +1,93 @@
+use crate::common::KeyTransformAction;
+use crate::provenance::identity_provenance;
+use crate::{HistorySegment, VitypeEngine};
+
+// ==================== Undo Commands ====================
+
+impl VitypeEngine {
+    /// Reverts the newest transform in the current word and locks it, as if the
+    /// escape key had been typed: "việt" becomes "viêtj" and "đ" becomes "dd".
+    pub(crate) fn undo_last_transform(&mut self) -> Option<KeyTransformAction> {
+        if self.is_caret_inside_word() {
+            return None;
+        }
+        self.dismiss_han_nom_candidates();
+        self.clear_predictions();
+        let (key, rebuilt, start) = self.word_without_last_mark()?;
+        let key = self.raw_buffer[key];
+        let delete_count = self.buffer.len() - start;
+
+        self.adopt_current_word_state_from(rebuilt);
+        self.raw_buffer.push(key);
+        self.buffer.push(key);
+        self.provenance.push(vec![self.raw_buffer.len() - 1]);
+        self.clear_transform_state();
+        self.transforms_locked = true;
+        self.push_word_snapshot();
+        self.repair_provenance_if_needed();
+
+        Some(KeyTransformAction {
+            delete_count,
+            text: self.buffer_string_from(start),
+        })
+    }
+
+    /// Switches the last committed word between its Vietnamese output and its raw
+    /// keystrokes. The action rewrites everything from the word to the caret, so
+    /// it also carries the boundary chars (and any word in progress) after it.
+    pub(crate) fn toggle_raw_last_word(&mut self) -> Option<KeyTransformAction> {
+        if self.is_caret_inside_word() {
+            return None;
+        }
+        let index = self
+            .history
+            .iter()
+            .rposition(|segment| matches!(segment, HistorySegment::Word(_)))?;
+        let mut trailing: String = self
+            .history
+            .iter()
+            .skip(index + 1)
+            .flat_map(|segment| match segment {
+                HistorySegment::Boundary(chars) => chars.clone(),
+                HistorySegment::Word(word) => word.buffer.clone(),
+            })
+            .collect();
+        trailing.extend(&self.buffer);
+
+        let HistorySegment::Word(word) = &self.history[index] else {
+            return None;
+        };
+        let delete_count = word.buffer.len() + trailing.chars().count();
+        let (buffer, provenance, is_foreign_mode, transforms_locked) =
+            if word.buffer != word.raw_buffer {
+                let identity = identity_provenance(word.raw_buffer.len());
+                (word.raw_buffer.clone(), identity, true, false)
+            } else {
+                let rebuilt = self.rebuild_current_word_from_raw(&word.raw_buffer);
+                // Keys that do not replay to themselves ("ass" escapes to "as") could
+                // not be toggled back, so such words are left alone.
+                if rebuilt.buffer == word.buffer || rebuilt.raw_buffer != word.raw_buffer {
+                    return None;
+                }
+                (
+                    rebuilt.buffer,
+                    rebuilt.provenance,
+                    rebuilt.is_foreign_mode,
+                    rebuilt.transforms_locked,
+                )
+            };
+
+        self.dismiss_han_nom_candidates();
+        self.clear_predictions();
+        let mut text: String = buffer.iter().collect();
+        text.push_str(&trailing);
+        if let HistorySegment::Word(word) = &mut self.history[index] {
+            word.buffer = buffer;
+            word.provenance = provenance;
+            word.is_foreign_mode = is_foreign_mode;
+            word.transforms_locked = transforms_locked;
+        }
+        self.repair_provenance_if_needed();
+        Some(KeyTransformAction { delete_count, text })
+    }
+}
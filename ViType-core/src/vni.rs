@@ -1,4 +1,4 @@
-use crate::common::{KeyTransformAction, WTransformKind};
+use crate::common::{InputMethod, KeyTransformAction, WTransformKind};
 use crate::diacritics::{apply_shape_preserving_tone, escape_shape_preserving_tone, VowelShape};
 use crate::VitypeEngine;
 
@@ -36,7 +36,7 @@ fn vni_apply_shape_for_key(ch: char, key: char) -> Option<char> {
     apply_shape_preserving_tone(ch, kind)
 }
 
-fn vni_tone_key_to_internal(ch: char) -> Option<char> {
+pub(super) fn vni_tone_key_to_internal(ch: char) -> Option<char> {
     Some(match ch {
         '1' => 's', // sắc
         '2' => 'f', // huyền
@@ -51,6 +51,29 @@ fn vni_tone_key_to_internal(ch: char) -> Option<char> {
 // ==================== VNI Methods on VitypeEngine ====================
 
 impl VitypeEngine {
+    pub(crate) fn set_smart_numeric_context(&mut self, enabled: bool) {
+        self.smart_numeric_context = enabled;
+    }
+
+    /// Escape hatch: lets digits in the current word act as VNI keys again until
+    /// the next boundary.
+    pub(crate) fn skip_numeric_context_for_word(&mut self) {
+        self.numeric_context_skipped = true;
+    }
+
+    /// Whether the digit `ch` belongs to a number rather than being a VNI key: the
+    /// word starts with a digit or follows a literal digit. Digits already used as
+    /// shape or tone keys are not in `buffer`, so "a61" still gives "ấ". Version and
+    /// decimal parts ("v1.2", "3,5") start new words with a digit, so they match too.
+    pub(super) fn is_vni_numeric_context(&self, ch: char) -> bool {
+        self.smart_numeric_context
+            && !self.numeric_context_skipped
+            && self.input_method == InputMethod::Vni
+            && ch.is_ascii_digit()
+            && (self.buffer.first().is_some_and(char::is_ascii_digit)
+                || self.buffer.last().is_some_and(char::is_ascii_digit))
+    }
+
     pub(super) fn try_vni_escape_sequence(&mut self, ch: char) -> Option<KeyTransformAction> {
         let last_key = self.last_transform_key?;
         if ch != last_key {
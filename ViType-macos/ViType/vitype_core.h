@@ -2,6 +2,7 @@
 #define VITYPE_CORE_H
 
 #include <stdbool.h>
+#include <stddef.h>
 #include <stdint.h>
 
 #ifdef __cplusplus
@@ -9,6 +10,8 @@ extern "C" {
 #endif
 
 typedef struct VitypeEngine VitypeEngine;
+typedef struct VitypeLexicon VitypeLexicon;
+typedef struct VitypeSessionManager VitypeSessionManager;
 
 typedef struct {
     bool has_action;
@@ -16,18 +19,181 @@ typedef struct {
     char *text;
 } VitypeTransformResult;
 
+typedef struct {
+    bool handled;
+    char *preedit;  // composing string; free with vitype_engine_free_string
+    int32_t caret;  // characters into preedit
+    char *commit;   // final text to insert before preedit; free with vitype_engine_free_string
+} VitypePreeditResult;
+
+typedef struct {
+    int32_t kind;   // 0 = move left, 1 = delete (backspace), 2 = insert, 3 = move right
+    int32_t count;  // characters moved, deleted or inserted
+    char *text;     // inserted text, NULL for other kinds
+} VitypeEditOp;
+
+typedef struct {
+    bool has_action;
+    VitypeEditOp *ops;
+    int32_t count;
+} VitypeEditScript;
+
+typedef struct {
+    int32_t byte_start;
+    int32_t byte_end;
+    int32_t char_start;
+    int32_t char_end;
+    int32_t kind;       // 0 = cluster, 1 = onset, 2 = coda, 3 = tone on stop final, 4 = misplaced tone
+    char *suggestions;  // '\n'-separated, best first
+} VitypeTextIssue;
+
+typedef struct {
+    VitypeTextIssue *issues;
+    int32_t count;
+} VitypeTextIssueList;
+
+typedef struct {
+    int32_t byte_start;
+    int32_t byte_end;
+    int32_t char_start;
+    int32_t char_end;
+    char *text;
+    double confidence;
+} VitypeRestoredToken;
+
+typedef struct {
+    char *text;
+    VitypeRestoredToken *tokens;
+    int32_t count;
+} VitypeRestoration;
+
 VitypeEngine *vitype_engine_new(void);
 void vitype_engine_free(VitypeEngine *engine);
 void vitype_engine_reset(VitypeEngine *engine);
 void vitype_engine_delete_last_character(VitypeEngine *engine);
+// Smart backspace (default off) removes the newest tone or shape mark first; the
+// result then carries the redraw and the host must not delete a char itself.
+VitypeTransformResult vitype_engine_backspace(VitypeEngine *engine);
+void vitype_engine_set_smart_backspace(VitypeEngine *engine, bool enabled);
+VitypeTransformResult vitype_engine_undo_last_transform(VitypeEngine *engine); // like the escape key
+// Last committed word: Vietnamese <-> raw keystrokes; the result also rewrites the boundary after it.
+VitypeTransformResult vitype_engine_toggle_raw_last_word(VitypeEngine *engine);
+// Replays the current word with validation off; remember keeps those keystrokes Vietnamese.
+VitypeTransformResult vitype_engine_force_vietnamese(VitypeEngine *engine, bool remember);
+void vitype_engine_clear_forced_vietnamese_words(VitypeEngine *engine);
+bool vitype_engine_check_consistency(const VitypeEngine *engine); // debug aid: provenance matches buffers
 void vitype_engine_set_auto_fix_tone(VitypeEngine *engine, bool enabled);
 void vitype_engine_set_free_tone_placement(VitypeEngine *engine, bool enabled);
+void vitype_engine_set_mixed_case_is_foreign(VitypeEngine *engine, bool enabled); // iPhone, macOS stay literal (default off)
+// VNI: digits in numbers ("302", "v1.2", "3a1") stay literal (default off).
+void vitype_engine_set_smart_numeric_context(VitypeEngine *engine, bool enabled);
+void vitype_engine_skip_numeric_context_for_word(VitypeEngine *engine);
 void vitype_engine_set_input_method(VitypeEngine *engine, int32_t method);  // 0 = Telex, 1 = VNI
 void vitype_engine_set_output_encoding(VitypeEngine *engine, int32_t encoding);
 void vitype_engine_set_tone_placement(VitypeEngine *engine, int32_t placement); // 0 = Orthographic, 1 = NucleusOnly
+void vitype_engine_set_i_y_style(VitypeEngine *engine, int32_t style); // 0 = Unchanged, 1 = prefer i (kĩ), 2 = prefer y (kỹ)
+// Orthography packs add letters such as ŏ, ñ, ƀ and relax validation for their words.
+bool vitype_engine_add_orthography_pack(VitypeEngine *engine, const char *pack_utf8);
+void vitype_engine_add_builtin_orthography_pack(VitypeEngine *engine, int32_t pack); // 0 = Central Highlands
+void vitype_engine_clear_orthography_packs(VitypeEngine *engine);
+// Loanwords such as "karaoke" stay literal; others keep Vietnamese rules or get a fixed spelling.
+bool vitype_engine_set_loanwords(VitypeEngine *engine, const char *lexicon_utf8);
+void vitype_engine_use_bundled_loanwords(VitypeEngine *engine);
+void vitype_engine_clear_loanwords(VitypeEngine *engine);
+// URLs, emails, paths and code identifiers stay literal until whitespace (all on by default).
+void vitype_engine_set_literal_contexts(VitypeEngine *engine, bool urls, bool emails, bool paths, bool identifiers);
 VitypeTransformResult vitype_engine_process(VitypeEngine *engine, const char *input_utf8);
 void vitype_engine_free_string(char *text);
 
+// Preedit (marked text) mode: keys update the composing string and boundaries commit
+// text; no deletions are ever requested.
+VitypePreeditResult vitype_engine_process_preedit(VitypeEngine *engine, const char *input_utf8);
+VitypePreeditResult vitype_engine_commit_preedit(VitypeEngine *engine);
+VitypePreeditResult vitype_engine_delete_preedit_character(VitypeEngine *engine);
+
+// Edit scripts: the same changes as vitype_engine_process, as caret-relative operations.
+// trimmed = true replaces the span between the common prefix and suffix.
+VitypeEditScript vitype_engine_process_edit_script(VitypeEngine *engine, const char *input_utf8, bool trimmed);
+void vitype_edit_script_free(VitypeEditScript script);
+
+// Caret-aware editing. Mirror caret moves with vitype_engine_move_cursor, then type and
+// delete with the functions below; vitype_engine_process resets when the caret is inside a word.
+void vitype_engine_set_history_word_limit(VitypeEngine *engine, int32_t limit); // default 3
+int32_t vitype_engine_move_cursor(VitypeEngine *engine, int32_t delta);
+bool vitype_engine_sync_surrounding_text(VitypeEngine *engine, const char *text_before_cursor_utf8); // true if state was rebuilt
+VitypeEditScript vitype_engine_process_at_caret(VitypeEngine *engine, const char *input_utf8);
+VitypeEditScript vitype_engine_delete_forward(VitypeEngine *engine);
+VitypeEditScript vitype_engine_delete_backward(VitypeEngine *engine);
+
+// Hán-Nôm candidates. When enabled, committing a word opens its list; selection
+// replaces the word (and re-emits the boundary typed after it).
+void vitype_engine_set_han_nom_enabled(VitypeEngine *engine, bool enabled);
+void vitype_engine_set_han_nom_page_size(VitypeEngine *engine, int32_t page_size);
+bool vitype_engine_load_han_nom_table(VitypeEngine *engine, const char *path_utf8);
+int32_t vitype_engine_open_han_nom_candidates(VitypeEngine *engine); // total candidates, 0 if none
+char *vitype_engine_han_nom_candidates(const VitypeEngine *engine);  // current page, '\n'-separated
+bool vitype_engine_han_nom_next_page(VitypeEngine *engine);
+bool vitype_engine_han_nom_previous_page(VitypeEngine *engine);
+VitypeTransformResult vitype_engine_han_nom_select(VitypeEngine *engine, int32_t index);
+VitypeTextIssueList vitype_engine_check_text(const VitypeEngine *engine, const char *text_utf8);
+// Confusable checks rank by the bundled lexicon, a placeholder with estimated counts
+// rather than corpus frequencies.
+// kind: 0 = hỏi/ngã, 1 = ch/tr, 2 = s/x, 3 = d/gi/r, 4 = final n/ng, 5 = final t/c; one suggestion
+VitypeTextIssueList vitype_engine_check_confusables(const VitypeEngine *engine, const char *text_utf8);
+VitypeTextIssueList vitype_engine_check_history_confusables(const VitypeEngine *engine);
+// kind: 0 = Telex, 1 = VNI; the suggestion is the converted word
+VitypeTextIssueList vitype_engine_find_unconverted_words(const VitypeEngine *engine, const char *text_utf8);
+void vitype_text_issue_list_free(VitypeTextIssueList list);
+
+// Lexicons load from the compact binary format or the bundled text count format.
+// The bundled default is a placeholder with estimated counts; load or train a real
+// lexicon for production ranking.
+VitypeLexicon *vitype_lexicon_load(const char *path_utf8);
+VitypeLexicon *vitype_lexicon_new(void);
+bool vitype_lexicon_train_file(VitypeLexicon *lexicon, const char *path_utf8); // adds a UTF-8 corpus
+bool vitype_lexicon_save(const VitypeLexicon *lexicon, const char *path_utf8);
+void vitype_lexicon_free(VitypeLexicon *lexicon);
+// lexicon may be NULL to use the bundled default
+VitypeRestoration vitype_engine_restore_diacritics(const VitypeEngine *engine, const VitypeLexicon *lexicon, const char *text_utf8);
+void vitype_restoration_free(VitypeRestoration restoration);
+
+// Prediction: completions of the current word, or next syllables after a committed one.
+// Suggestions are indexed as returned by the last vitype_engine_predictions call.
+void vitype_engine_set_prediction_lexicon(VitypeEngine *engine, const VitypeLexicon *lexicon); // copied
+char *vitype_engine_predictions(VitypeEngine *engine, int32_t limit); // '\n'-separated, best first
+VitypeTransformResult vitype_engine_accept_prediction(VitypeEngine *engine, int32_t index);
+VitypeTransformResult vitype_engine_reject_prediction(VitypeEngine *engine, int32_t index);
+
+// Text utilities; returned strings are freed with vitype_engine_free_string.
+char *vitype_normalize_tone_placement(const char *text_utf8, int32_t placement); // 0 = Orthographic, 1 = NucleusOnly
+char *vitype_normalize_i_y(const char *text_utf8, int32_t style); // same codes as vitype_engine_set_i_y_style
+char *vitype_transcribe_ipa(const char *text_utf8, int32_t dialect); // 0 = Hanoi, 1 = Saigon
+char *vitype_all_syllables(void); // every valid syllable in every tone, '\n'-separated
+
+// Vietnamese collation. case_order: 0 = ignore case, 1 = lowercase first, 2 = uppercase first.
+// Sort keys compare with memcmp and are freed with vitype_collation_sort_key_free.
+int32_t vitype_collation_compare(const char *a_utf8, const char *b_utf8, int32_t case_order, bool digraphs_as_units);
+uint8_t *vitype_collation_sort_key(const char *text_utf8, int32_t case_order, bool digraphs_as_units, size_t *out_length);
+void vitype_collation_sort_key_free(uint8_t *key, size_t length);
+
+// One engine per host context id (window or field), least recently activated evicted first.
+// Sessions share the settings of the configuration engine; returned engines are owned by the
+// manager and stay valid until their session is dropped or evicted. Change settings only on the
+// configuration engine, then call vitype_session_manager_apply_configuration: writes through the
+// configuration pointer reach no session before that call, and settings changed on a session's
+// own engine are replaced at the next configuration change.
+VitypeSessionManager *vitype_session_manager_new(void);
+void vitype_session_manager_free(VitypeSessionManager *manager);
+VitypeEngine *vitype_session_manager_configuration(VitypeSessionManager *manager);
+void vitype_session_manager_apply_configuration(VitypeSessionManager *manager); // required after every settings change; pushes them to the active session, the others on activation
+void vitype_session_manager_set_max_sessions(VitypeSessionManager *manager, int32_t limit); // default 8
+VitypeEngine *vitype_session_manager_activate(VitypeSessionManager *manager, const char *context_utf8);
+void vitype_session_manager_deactivate(VitypeSessionManager *manager);
+VitypeEngine *vitype_session_manager_active_engine(VitypeSessionManager *manager); // NULL when none is active
+bool vitype_session_manager_drop(VitypeSessionManager *manager, const char *context_utf8);
+void vitype_session_manager_drop_all(VitypeSessionManager *manager);
+int32_t vitype_session_manager_session_count(const VitypeSessionManager *manager);
+
 #ifdef __cplusplus
 }
 #endif